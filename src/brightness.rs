@@ -1,5 +1,15 @@
 //! Display brightness
 
+/// DBV value of each perceptual brightness percentage, `255 * (percent / 100) ^ 2.2` rounded,
+/// at least 1 above 0%
+const PERCEPTUAL: [u8; 101] = [
+    0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 3, 3, 4, 5, 5, 6, 7, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+    17, 18, 19, 21, 22, 24, 25, 27, 29, 30, 32, 34, 36, 38, 40, 42, 44, 46, 48, 51, 53, 55, 58, 60,
+    63, 66, 68, 71, 74, 77, 80, 83, 86, 89, 92, 96, 99, 102, 106, 109, 113, 116, 120, 124, 128,
+    131, 135, 139, 143, 148, 152, 156, 160, 165, 169, 174, 178, 183, 188, 192, 197, 202, 207, 212,
+    217, 223, 228, 233, 238, 244, 249, 255,
+];
+
 /// Struct that holds display brightness
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Brightness {
@@ -41,6 +51,18 @@ impl Brightness {
         Self { brightness }
     }
 
+    /// Create a new `Brightness` from a perceived brightness `percent`, from 0 to 100
+    ///
+    /// The eye responds to light roughly like a 2.2 gamma, so the raw value follows that
+    /// curve: equal percentage steps look equal, which suits auto-brightness. Only 0% gives
+    /// a zero brightness, see [`ZeroBrightnessPolicy`], and percentages above 100 are
+    /// clamped. Use [`custom`](Self::custom) for raw control.
+    #[must_use]
+    pub const fn from_perceptual(percent: u8) -> Self {
+        let percent = if percent > 100 { 100 } else { percent };
+        Self::custom(PERCEPTUAL[percent as usize])
+    }
+
     /// Returns the brightness as a `u8`.
     ///
     /// # Note
@@ -50,3 +72,16 @@ impl Brightness {
         self.brightness
     }
 }
+
+/// What `set_brightness` does when the brightness reaches zero
+///
+/// Some panels still show a faint backlit glow at the lowest brightness level.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ZeroBrightnessPolicy {
+    /// Only write the brightness value, the display stays on (default)
+    #[default]
+    Dim,
+    /// Also turn the display off (28h) at zero brightness, and back on (29h) as soon as
+    /// the brightness rises above zero
+    Blank,
+}
@@ -0,0 +1,281 @@
+//! Capture Interface
+//!
+//! A [`WriteOnlyDataCommand`] that records every byte sent to the display instead of
+//! driving hardware. Combined with [`CaptureInterface::replay`] it rebuilds the pushed
+//! frames on the host, so rendering can be compared against golden pixels without a panel.
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use heapless::Vec;
+
+#[cfg(feature = "async")]
+use display_interface::AsyncWriteOnlyDataCommand;
+
+/// A single byte captured on the interface
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Captured {
+    /// Byte sent with DC low
+    Command(u8),
+    /// Byte sent with DC high
+    Data(u8),
+}
+
+/// Memory window targeted by the last replayed memory write
+///
+/// Both coordinates are inclusive, exactly as sent by 2Ah/2Bh.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CapturedWindow {
+    /// (`x`, `y`) upper left corner
+    pub start: (u16, u16),
+    /// (`x`, `y`) lower right corner
+    pub end: (u16, u16),
+}
+
+/// Capture Interface
+///
+/// Records up to `N` bytes in wire order. Once full, any further write fails with
+/// [`DisplayError::BusWriteError`].
+#[derive(Debug, Clone, Default)]
+pub struct CaptureInterface<const N: usize> {
+    bytes: Vec<Captured, N>,
+}
+
+impl<const N: usize> CaptureInterface<N> {
+    /// Create an empty capture interface
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { bytes: Vec::new() }
+    }
+
+    /// Bytes captured so far, in wire order
+    #[must_use]
+    pub fn captured(&self) -> &[Captured] {
+        &self.bytes
+    }
+
+    /// Forget everything captured so far
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+
+    /// Replay the captured stream into `frame`, a row-major panel memory of `stride` columns.
+    ///
+    /// Column (2Ah) and row (2Bh) address commands define the window, and pixels following a
+    /// memory write (2Ch/3Ch) are decoded as big-endian Rgb565 and written at the window
+    /// cursor. Pixels landing outside `frame`, or past its `stride`, are ignored.
+    ///
+    /// Returns the window of the last memory write, or `None` if nothing was written.
+    pub fn replay(&self, frame: &mut [u16], stride: usize) -> Option<CapturedWindow> {
+        let mut command = 0u8;
+        let mut params = [0u8; 4];
+        let mut param_len = 0;
+        let mut columns = (0u16, 0u16);
+        let mut rows = (0u16, 0u16);
+        // wide enough to step past a window ending at 0xFFFF
+        let mut cursor = (0u32, 0u32);
+        let mut high_byte = None;
+        let mut window = None;
+
+        for captured in &self.bytes {
+            match *captured {
+                Captured::Command(cmd) => {
+                    command = cmd;
+                    param_len = 0;
+                    high_byte = None;
+
+                    if cmd == 0x2C {
+                        cursor = (columns.0.into(), rows.0.into());
+                    }
+                    if cmd == 0x2C || cmd == 0x3C {
+                        window = Some(CapturedWindow {
+                            start: (columns.0, rows.0),
+                            end: (columns.1, rows.1),
+                        });
+                    }
+                }
+                Captured::Data(byte) => match command {
+                    0x2A | 0x2B if param_len < params.len() => {
+                        params[param_len] = byte;
+                        param_len += 1;
+
+                        if param_len == params.len() {
+                            let range = (
+                                u16::from_be_bytes([params[0], params[1]]),
+                                u16::from_be_bytes([params[2], params[3]]),
+                            );
+                            if command == 0x2A {
+                                columns = range;
+                            } else {
+                                rows = range;
+                            }
+                        }
+                    }
+                    0x2C | 0x3C => {
+                        let Some(high) = high_byte.take() else {
+                            high_byte = Some(byte);
+                            continue;
+                        };
+
+                        if cursor.1 <= rows.1.into() && (cursor.0 as usize) < stride {
+                            let idx = cursor.1 as usize * stride + cursor.0 as usize;
+                            if let Some(pixel) = frame.get_mut(idx) {
+                                *pixel = u16::from_be_bytes([high, byte]);
+                            }
+                        }
+
+                        cursor.0 += 1;
+                        if cursor.0 > columns.1.into() {
+                            cursor = (columns.0.into(), cursor.1 + 1);
+                        }
+                    }
+                    _ => {}
+                },
+            }
+        }
+
+        window
+    }
+
+    fn push(&mut self, byte: Captured) -> Result<(), DisplayError> {
+        self.bytes
+            .push(byte)
+            .map_err(|_full| DisplayError::BusWriteError)
+    }
+
+    fn record(
+        &mut self,
+        format: DataFormat<'_>,
+        wrap: fn(u8) -> Captured,
+    ) -> Result<(), DisplayError> {
+        match format {
+            DataFormat::U8(slice) => slice.iter().try_for_each(|&b| self.push(wrap(b))),
+            DataFormat::U16(slice) => slice
+                .iter()
+                .flat_map(|w| w.to_ne_bytes())
+                .try_for_each(|b| self.push(wrap(b))),
+            DataFormat::U16BE(slice) => slice
+                .iter()
+                .flat_map(|w| w.to_be_bytes())
+                .try_for_each(|b| self.push(wrap(b))),
+            DataFormat::U16LE(slice) => slice
+                .iter()
+                .flat_map(|w| w.to_le_bytes())
+                .try_for_each(|b| self.push(wrap(b))),
+            DataFormat::U8Iter(iter) => iter.map(wrap).try_for_each(|b| self.push(b)),
+            DataFormat::U16BEIter(iter) => iter
+                .flat_map(u16::to_be_bytes)
+                .try_for_each(|b| self.push(wrap(b))),
+            DataFormat::U16LEIter(iter) => iter
+                .flat_map(u16::to_le_bytes)
+                .try_for_each(|b| self.push(wrap(b))),
+            _ => Err(DisplayError::DataFormatNotImplemented),
+        }
+    }
+}
+
+impl<const N: usize> WriteOnlyDataCommand for CaptureInterface<N> {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.record(cmd, Captured::Command)
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.record(buf, Captured::Data)
+    }
+}
+
+// `DataFormat` iterators are not `Send`, neither are the futures borrowing them
+#[cfg(feature = "async")]
+#[allow(clippy::future_not_send)]
+impl<const N: usize> AsyncWriteOnlyDataCommand for CaptureInterface<N> {
+    async fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.record(cmd, Captured::Command)
+    }
+
+    async fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.record(buf, Captured::Data)
+    }
+}
+
+/// Split a captured stream into commands and their parameters
+#[cfg(test)]
+pub(crate) fn commands(captured: &[Captured]) -> std::vec::Vec<(u8, std::vec::Vec<u8>)> {
+    let mut commands = std::vec::Vec::new();
+
+    for byte in captured {
+        match *byte {
+            Captured::Command(cmd) => commands.push((cmd, std::vec::Vec::new())),
+            Captured::Data(data) => {
+                if let Some(&mut (_cmd, ref mut params)) = commands.last_mut() {
+                    params.push(data);
+                }
+            }
+        }
+    }
+
+    commands
+}
+
+/// Parameters of every `cmd` in a captured stream, in wire order
+#[cfg(test)]
+pub(crate) fn params(captured: &[Captured], cmd: u8) -> std::vec::Vec<std::vec::Vec<u8>> {
+    commands(captured)
+        .into_iter()
+        .filter(|&(sent, _)| sent == cmd)
+        .map(|(_cmd, params)| params)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{display::DisplayResolution240x240, rotation::DisplayRotation, Gc9a01};
+
+    #[test]
+    fn replay_rebuilds_the_flushed_frame() -> Result<(), DisplayError> {
+        let mut display = Gc9a01::new(
+            CaptureInterface::<4096>::new(),
+            DisplayResolution240x240,
+            DisplayRotation::Rotate0,
+        )
+        .into_buffered_graphics();
+        display.set_pixels((100, 50), (109, 53), 1..)?;
+        display.set_pixel(200, 60, 0xF800);
+        display.flush()?;
+
+        let mut frame = std::vec![0u16; 240 * 240];
+        let window = display.interface().replay(&mut frame, 240);
+
+        assert_eq!(
+            window,
+            Some(CapturedWindow {
+                start: (100, 60),
+                end: (200, 60)
+            })
+        );
+        for (idx, &pixel) in frame.iter().enumerate() {
+            assert_eq!(
+                Some(pixel),
+                display.get_pixel((idx % 240) as u32, (idx / 240) as u32)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn replay_stops_at_the_last_address() -> Result<(), DisplayError> {
+        let mut capture = CaptureInterface::<64>::new();
+        WriteOnlyDataCommand::send_commands(&mut capture, DataFormat::U8(&[0x2A]))?;
+        WriteOnlyDataCommand::send_data(&mut capture, DataFormat::U8(&[0xFF, 0xFE, 0xFF, 0xFF]))?;
+        WriteOnlyDataCommand::send_commands(&mut capture, DataFormat::U8(&[0x2B]))?;
+        WriteOnlyDataCommand::send_data(&mut capture, DataFormat::U8(&[0xFF, 0xFF, 0xFF, 0xFF]))?;
+        WriteOnlyDataCommand::send_commands(&mut capture, DataFormat::U8(&[0x2C]))?;
+        WriteOnlyDataCommand::send_data(&mut capture, DataFormat::U16BE(&mut [1, 2, 3, 4]))?;
+
+        let mut frame = [0u16; 4];
+        capture.replay(&mut frame, 2);
+
+        assert_eq!(frame, [0; 4]);
+
+        Ok(())
+    }
+}
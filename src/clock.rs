@@ -0,0 +1,13 @@
+//! Clock abstraction
+
+/// Monotonic tick source
+///
+/// Used to pace frames against a deadline, see
+/// [`Gc9a01::flush_before`](crate::Gc9a01::flush_before).
+pub trait Clock {
+    /// Number of ticks per second
+    const TICKS_PER_SECOND: u64;
+
+    /// Current tick count, must never go backwards
+    fn now(&self) -> u64;
+}
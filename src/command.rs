@@ -4,9 +4,38 @@
 
 use display_interface::{DataFormat::U8, DisplayError, WriteOnlyDataCommand};
 
+#[cfg(feature = "async")]
+use display_interface::AsyncWriteOnlyDataCommand;
+
 /// GC9A01 Commands
 #[derive(Debug, Copy, Clone)]
 pub enum Command {
+    /// No Operation (00h)
+    ///
+    /// ## Description
+    ///
+    /// This command is an empty command; it does not have any effect on the display module.
+    /// However it can be used to terminate Frame Memory Write or Read.
+    ///
+    Nop,
+
+    /// Software Reset (01h)
+    ///
+    /// ## Description
+    ///
+    /// When the Software Reset command is written, it causes a software reset. It resets the
+    /// commands and parameters to their S/W Reset default values, the frame memory contents are
+    /// unaffected.
+    ///
+    /// ## Restriction
+    ///
+    /// It will be necessary to wait 5msec before sending new command following software reset.
+    /// The display module loads all display supplier factory default values to the registers
+    /// during this 5msec. If Software Reset is applied during Sleep Out mode, it will be
+    /// necessary to wait 120msec before sending Sleep out command.
+    ///
+    SoftwareReset,
+
     /// Set Sleep mode (10h/11h)
     ///
     /// This command turns on/off sleep mode.
@@ -138,16 +167,34 @@ pub enum Command {
     /// or 013Fh (When MADCTL’s B5 = 1), data of out of range will be ignored.
     ///
     RowAddressSet(u16, u16),
-    // Partial Area (start, end) (30)
-    // TODO:>
-    // TODO: PartialArea(u16, u16),
-    //
+
+    /// Partial Area (start, end) (30h)
+    ///
+    /// ## Parameters
+    ///
+    /// * SR `.0` => Start Row
+    /// * ER `.1` => End Row
+    ///
+    /// ## Description
+    ///
+    /// This command defines the partial mode's display area. The first pair of parameters
+    /// describes the start row (SR) and the second pair the end row (ER), both counted from the
+    /// top of the frame memory. The partial area is shown once Partial mode (12h) is on, and
+    /// the rows outside of it are left blank.
+    ///
+    /// ## Restriction
+    ///
+    /// SR [15:0] always must be equal to or less than ER [15:0].
+    ///
+    PartialArea(u16, u16),
+
     /// Vertical Scrolling Definition (33h)
     ///
     /// ## Parameters
     ///
     /// * TFA `.0` => Top Fixed Area
     /// * VSA `.1` => Vertical Scrolling Area
+    /// * BFA `.2` => Bottom Fixed Area
     ///
     /// This command is used to define area of frame memory where MCU can access.
     ///
@@ -174,7 +221,10 @@ pub enum Command {
     /// from Frame Memory appears
     /// immediately after the top most line of the Top Fixed Area
     ///
-    VertialScrollDef(u16, u16),
+    /// The 5th & 6th parameter BFA [15...0] describes the Bottom Fixed Area. TFA, VSA and BFA
+    /// must add up to the number of lines of the Frame Memory.
+    ///
+    VertialScrollDef(u16, u16, u16),
 
     /// Tearing Effect Line OFF (35h)
     /// Tearing Effect Line OFF (34h)
@@ -203,6 +253,8 @@ pub enum Command {
 
     /// Memory Access Control (36h)
     ///
+    /// Prefer [`MadctlBuilder`] over the positional flags.
+    ///
     /// This command defines read/write scanning direction of frame memory.
     /// This command makes no change on the other driver status
     /// ## Parameters
@@ -292,6 +344,8 @@ pub enum Command {
     /// display reaches line equal the value of STS[8:0].
     ///
     /// __NOTE__: that set_tear_scanline with STS is equivalent to set_tear_on with 8+GateN(N=1、2、3...240)
+    ///
+    /// The encoded `STS + 8` saturates at the 9-bit maximum (1FFh) instead of wrapping.
     SetTearScanline(u16),
 
     /// Write Display Brightness (51h)
@@ -415,7 +469,7 @@ pub enum Command {
     ///
     /// ## Parameters
     ///
-    /// * vdd_ad `.0` => TODO
+    /// * vdd_ad `.0` => VCORE output level, see [`VddAd::closest`] to pick it in millivolts
     ///
     VCoreVoltageControl(VddAd),
 
@@ -469,10 +523,15 @@ pub enum Command {
     /// ## Parameters
     ///
     /// * DINV `.0` => [`DINVMode`]
-    /// * (unused) RTN1 `.1` => TODO
-    /// * (undocumented) RTN2 `.2` => TODO (Misleading information)
+    /// * RTN1 `.1` => 4-bit line period divider, sent in the low nibble of the 1st parameter
+    /// * RTN2 `.2` => line period divider, sent as the 2nd parameter
+    ///
+    /// ## Description
+    ///
+    /// The refresh rate falls as the line period grows. The datasheet gives no formula
+    /// mapping RTN1/RTN2 to Hz, so measure the period of the TE output to settle on values.
     ///
-    FrameRate(DINVMode),
+    FrameRate(DINVMode, u8, u8),
 
     /// SPI 2data Control (E9h)
     ///
@@ -739,11 +798,47 @@ impl Command {
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
-    #[allow(clippy::too_many_lines)]
     pub fn send<DI>(self, iface: &mut DI) -> Result<(), DisplayError>
     where
         DI: WriteOnlyDataCommand,
     {
+        let (data, len) = self.encode();
+
+        // Send command over the interface
+        iface.send_commands(U8(&[data[0]]))?;
+        if len > 1 {
+            iface.send_data(U8(&data[1..len]))?;
+        }
+        Ok(())
+    }
+
+    /// Send command to [`Gc9a01`] over an async interface
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    #[cfg(feature = "async")]
+    pub async fn send_async<DI>(self, iface: &mut DI) -> Result<(), DisplayError>
+    where
+        DI: AsyncWriteOnlyDataCommand,
+    {
+        let (data, len) = self.encode();
+
+        iface.send_commands(U8(&[data[0]])).await?;
+        if len > 1 {
+            iface.send_data(U8(&data[1..len])).await?;
+        }
+        Ok(())
+    }
+
+    /// Encode the command as it goes on the wire: the command byte followed by its
+    /// parameters, and the number of bytes used
+    ///
+    /// [`send`](Self::send) writes the first byte with DC low and the `len - 1` following ones
+    /// with DC high. The rest of the array is zeroed.
+    #[must_use]
+    #[allow(clippy::too_many_lines)]
+    pub const fn encode(self) -> ([u8; 13], usize) {
         // 16bits command (2bytes)
         // 16bits param_1 (2bytes)
         // 16bits param_2 (2bytes)
@@ -818,15 +913,33 @@ impl Command {
                 ],
                 5,
             ),
-            Self::VertialScrollDef(tfa, vsa) => (
+            Self::PartialArea(sr, er) => (
+                [
+                    0x30,
+                    (sr >> 8) as u8,
+                    (sr & 0xFF) as u8,
+                    (er >> 8) as u8,
+                    (er & 0xFF) as u8,
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                ],
+                5,
+            ),
+            Self::VertialScrollDef(tfa, vsa, bfa) => (
                 [
                     0x33,
                     (tfa >> 8) as u8,
                     (tfa & 0xFF) as u8,
                     (vsa >> 8) as u8,
                     (vsa & 0xFF) as u8,
-                    0,
-                    0,
+                    (bfa >> 8) as u8,
+                    (bfa & 0xFF) as u8,
                     0,
                     0,
                     0,
@@ -834,7 +947,7 @@ impl Command {
                     0,
                     0,
                 ],
-                5,
+                7,
             ),
             Self::TearingEffectLine(mode) => {
                 ([0x34 | mode as u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 1)
@@ -876,24 +989,28 @@ impl Command {
                 ],
                 2,
             ),
-            Self::SetTearScanline(sts) => (
-                [
-                    0x44,
-                    (((sts + 8) & 0x100) >> 8) as u8,
-                    ((sts + 8) & 0xFF) as u8,
-                    0,
-                    0,
-                    0,
-                    0,
-                    0,
-                    0,
-                    0,
-                    0,
-                    0,
-                    0,
-                ],
-                3,
-            ),
+            Self::SetTearScanline(sts) => {
+                // STS + 8 must fit the 9-bit field
+                let line = if sts > 0x1FF - 8 { 0x1FF } else { sts + 8 };
+                (
+                    [
+                        0x44,
+                        ((line & 0x100) >> 8) as u8,
+                        (line & 0xFF) as u8,
+                        0,
+                        0,
+                        0,
+                        0,
+                        0,
+                        0,
+                        0,
+                        0,
+                        0,
+                        0,
+                    ],
+                    3,
+                )
+            }
             Self::DisplayBrightness(dbv) => ([0x51, dbv, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 2),
             Self::CtrlDisplay(bctrl, dd, bl) => (
                 [
@@ -1052,11 +1169,11 @@ impl Command {
             Self::Vreg2aVoltageControl(value) => {
                 ([0xC9, value, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 2)
             }
-            Self::FrameRate(divn_mode) => (
+            Self::FrameRate(divn_mode, rtn1, rtn2) => (
                 [
                     0xE8,
-                    (divn_mode as u8 & 0b111) << 4,
-                    0,
+                    (divn_mode as u8 & 0b111) << 4 | (rtn1 & 0b1111),
+                    rtn2,
                     0,
                     0,
                     0,
@@ -1068,7 +1185,7 @@ impl Command {
                     0,
                     0,
                 ],
-                2,
+                3,
             ),
             Self::Spi2dataControl(data2_en, data_format) => (
                 [
@@ -1112,6 +1229,8 @@ impl Command {
                 ],
                 4,
             ),
+            Self::Nop => ([0x00, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 1),
+            Self::SoftwareReset => ([0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 1),
             Self::InnerRegisterEnable1 => ([0xFE, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 1),
             Self::InnerRegisterEnable2 => ([0xEF, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 1),
             Self::SetGamma1(gamma) => (
@@ -1283,13 +1402,105 @@ impl Command {
             Self::SetUndocumented098h => ([0x98, 0x3e, 0x07, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 3),
         };
 
-        // Send command over the interface
-        // TODO: do something better
-        iface.send_commands(U8(&[data[0]]))?;
-        if len > 1 {
-            iface.send_data(U8(&data[1..len]))?;
+        (data, len)
+    }
+}
+
+/// Memory Access Control (36h) builder
+///
+/// Names the six positional flags of [`Command::MemoryAccessControl`]. Every flag starts
+/// [`Logical::Off`]:
+///
+/// ```ignore
+/// let madctl = MadctlBuilder::new()
+///     .row_order(Logical::On)
+///     .vertical_refresh(Logical::On)
+///     .bgr(Logical::On)
+///     .build();
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MadctlBuilder {
+    row_order: Logical,
+    col_order: Logical,
+    row_col_exchange: Logical,
+    vertical_refresh: Logical,
+    bgr: Logical,
+    horizontal_refresh: Logical,
+}
+
+impl Default for MadctlBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MadctlBuilder {
+    /// Builder with every flag off
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            row_order: Logical::Off,
+            col_order: Logical::Off,
+            row_col_exchange: Logical::Off,
+            vertical_refresh: Logical::Off,
+            bgr: Logical::Off,
+            horizontal_refresh: Logical::Off,
         }
-        Ok(())
+    }
+
+    /// MY, Row Address Order
+    #[must_use]
+    pub const fn row_order(mut self, value: Logical) -> Self {
+        self.row_order = value;
+        self
+    }
+
+    /// MX, Column Address Order
+    #[must_use]
+    pub const fn col_order(mut self, value: Logical) -> Self {
+        self.col_order = value;
+        self
+    }
+
+    /// MV, Row / Column Exchange
+    #[must_use]
+    pub const fn row_col_exchange(mut self, value: Logical) -> Self {
+        self.row_col_exchange = value;
+        self
+    }
+
+    /// ML, LCD vertical refresh direction
+    #[must_use]
+    pub const fn vertical_refresh(mut self, value: Logical) -> Self {
+        self.vertical_refresh = value;
+        self
+    }
+
+    /// BGR, color filter order (on for a BGR panel)
+    #[must_use]
+    pub const fn bgr(mut self, value: Logical) -> Self {
+        self.bgr = value;
+        self
+    }
+
+    /// MH, LCD horizontal refresh direction
+    #[must_use]
+    pub const fn horizontal_refresh(mut self, value: Logical) -> Self {
+        self.horizontal_refresh = value;
+        self
+    }
+
+    /// Build the [`Command::MemoryAccessControl`] command
+    #[must_use]
+    pub const fn build(&self) -> Command {
+        Command::MemoryAccessControl(
+            self.row_order,
+            self.col_order,
+            self.row_col_exchange,
+            self.vertical_refresh,
+            self.bgr,
+            self.horizontal_refresh,
+        )
     }
 }
 
@@ -1470,7 +1681,7 @@ impl From<u8> for SSMode {
     fn from(val: u8) -> Self {
         match val {
             0 => Self::S1toS360,
-            _ => Self::S1toS360,
+            _ => Self::S360toS1,
         }
     }
 }
@@ -1613,15 +1824,17 @@ pub enum DataFormatMDT {
     Color4M2Or3PixelPerTransition = 5,
 }
 
+/// The reserved value 3 falls back to [`DataFormatMDT::Color65k1PixelPerTransition`], the
+/// reset value, and values above 5 to [`DataFormatMDT::Color4M2Or3PixelPerTransition`].
 impl From<u8> for DataFormatMDT {
     fn from(val: u8) -> Self {
         match val {
-            0 => Self::Color65k1PixelPerTransition,
             1 => Self::Color262k1PixelPerTransition,
             2 => Self::Color262k2Or3PixelPerTransition,
-            3 => Self::Color4Mk1PixelPerTransition,
-            4 => Self::Color4M2Or3PixelPerTransition,
-            _ => Self::Color4M2Or3PixelPerTransition,
+            4 => Self::Color4Mk1PixelPerTransition,
+            5.. => Self::Color4M2Or3PixelPerTransition,
+            // 3 is reserved
+            _ => Self::Color65k1PixelPerTransition,
         }
     }
 }
@@ -1655,7 +1868,7 @@ pub enum VddAd {
     VCore1_590V = 0x02,
     VCore1_638V = 0x03,
     VCore1_714V = 0x04,
-    VCore1_279V = 0x05,
+    VCore1_792V = 0x05,
     VCore1_859V = 0x06,
     VCore1_925V = 0x07,
     VCore1_994V = 0x08,
@@ -1676,7 +1889,7 @@ impl From<u8> for VddAd {
             0x02 => Self::VCore1_590V,
             0x03 => Self::VCore1_638V,
             0x04 => Self::VCore1_714V,
-            0x05 => Self::VCore1_279V,
+            0x05 => Self::VCore1_792V,
             0x06 => Self::VCore1_859V,
             0x07 => Self::VCore1_925V,
             0x08 => Self::VCore1_994V,
@@ -1692,6 +1905,67 @@ impl From<u8> for VddAd {
     }
 }
 
+impl VddAd {
+    /// Former name of [`VddAd::VCore1_792V`], misspelled
+    #[deprecated(note = "Use `VddAd::VCore1_792V` instead")]
+    #[allow(non_upper_case_globals)]
+    pub const VCore1_279V: Self = Self::VCore1_792V;
+
+    /// Every distinct level, in increasing voltage
+    const LEVELS: [Self; 13] = [
+        Self::VCore1_483V,
+        Self::VCore1_545V,
+        Self::VCore1_590V,
+        Self::VCore1_638V,
+        Self::VCore1_714V,
+        Self::VCore1_792V,
+        Self::VCore1_859V,
+        Self::VCore1_925V,
+        Self::VCore1_994V,
+        Self::VCore2_109V,
+        Self::VCore2_193V,
+        Self::VCore2_286V,
+        Self::VCore2_385V,
+    ];
+
+    /// Output level in millivolts
+    #[must_use]
+    pub const fn millivolts(self) -> u16 {
+        match self {
+            Self::VCore1_483V => 1483,
+            Self::VCore1_545V => 1545,
+            Self::VCore1_590V => 1590,
+            Self::VCore1_638V => 1638,
+            Self::VCore1_714V => 1714,
+            Self::VCore1_792V => 1792,
+            Self::VCore1_859V => 1859,
+            Self::VCore1_925V => 1925,
+            Self::VCore1_994V => 1994,
+            Self::VCore2_109V => 2109,
+            Self::VCore2_193V => 2193,
+            Self::VCore2_286V => 2286,
+            Self::VCore2_385V => 2385,
+            Self::VCore1_713V | Self::VCore1_713Ve | Self::VCore1_713Vf => 1713,
+        }
+    }
+
+    /// Supported level nearest to `millivolts`, the lower one on a tie
+    #[must_use]
+    pub const fn closest(millivolts: u16) -> Self {
+        let mut closest = Self::LEVELS[0];
+        let mut idx = 1;
+        while idx < Self::LEVELS.len() {
+            let level = Self::LEVELS[idx];
+            if level.millivolts().abs_diff(millivolts) < closest.millivolts().abs_diff(millivolts) {
+                closest = level;
+            }
+            idx += 1;
+        }
+
+        closest
+    }
+}
+
 /// Gate Output Scan Direction
 /// Sets the direction of scan by the gate driver in the range determined by SCN [4:0] and NL
 /// [4:0]. The scan direction determined by GS = 0 can be reversed by setting GS = 1.
@@ -1815,3 +2089,37 @@ pub struct Gamma4 {
     /// dig2gam_vr63_p
     pub vr63_p: u8,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ss_mode_from_u8_round_trips() {
+        assert_eq!(SSMode::from(0), SSMode::S1toS360);
+        assert_eq!(SSMode::from(1), SSMode::S360toS1);
+        assert_eq!(SSMode::from(SSMode::S360toS1 as u8), SSMode::S360toS1);
+    }
+
+    #[test]
+    fn data_format_mdt_from_u8_round_trips() {
+        for mdt in [
+            DataFormatMDT::Color65k1PixelPerTransition,
+            DataFormatMDT::Color262k1PixelPerTransition,
+            DataFormatMDT::Color262k2Or3PixelPerTransition,
+            DataFormatMDT::Color4Mk1PixelPerTransition,
+            DataFormatMDT::Color4M2Or3PixelPerTransition,
+        ] {
+            assert_eq!(DataFormatMDT::from(mdt as u8), mdt);
+        }
+
+        assert_eq!(
+            DataFormatMDT::from(3),
+            DataFormatMDT::Color65k1PixelPerTransition
+        );
+        assert_eq!(
+            DataFormatMDT::from(7),
+            DataFormatMDT::Color4M2Or3PixelPerTransition
+        );
+    }
+}
@@ -6,7 +6,8 @@ use display_interface::{DisplayError, WriteOnlyDataCommand};
 use embedded_hal::delay::DelayNs;
 
 use crate::command::{
-    Command, DINVMode, Dbi, Dpi, GSMode, Gamma1, Gamma2, Gamma3, Gamma4, Logical, SSMode,
+    Command, DINVMode, Dbi, Dpi, GSMode, Gamma1, Gamma2, Gamma3, Gamma4, Logical, MadctlBuilder,
+    SSMode,
 };
 
 /// Screen information
@@ -31,11 +32,32 @@ pub trait DisplayDefinition {
     /// The driver maximum rows    
     const ROWS: u16 = 240;
 
+    /// Number of pixels of the active area
+    const PIXELS: usize = Self::WIDTH as usize * Self::HEIGHT as usize;
+
+    /// Whether the panel has a BGR color filter, the MADCTL BGR bit a new driver starts with
+    const DEFAULT_BGR: bool = true;
+
+    /// Invisible pixels at both ends of each row for round panels, `None` for rectangular ones.
+    ///
+    /// When set, `flush` clips every row of the dirty region to the visible chord so no
+    /// bandwidth is spent on the hidden corners. See [`round_insets`].
+    const VISIBLE_INSETS: Option<&'static [u16]> = None;
+
+    /// Initialisation sequence run by the default [`configure`](Self::configure)
+    ///
+    /// Describe a new panel by listing its vendor sequence here instead of writing
+    /// `configure` by hand. The column and row address ranges are not part of it: the driver
+    /// derives them from the definition geometry on every window update.
+    const INIT: &'static [InitStep] = DEFAULT_INIT;
+
     /// Buffer type Sized
-    type Buffer: AsMut<[u16]> + NewZeroed;
+    type Buffer: AsMut<[u16]> + AsRef<[u16]> + NewZeroed;
 
     /// Configuration hook to configure model-dependent configuration
     ///
+    /// Runs [`INIT`](Self::INIT) by default.
+    ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
@@ -43,7 +65,38 @@ pub trait DisplayDefinition {
         &self,
         iface: &mut impl WriteOnlyDataCommand,
         delay: &mut impl DelayNs,
-    ) -> Result<(), DisplayError>;
+    ) -> Result<(), DisplayError> {
+        run_init(Self::INIT, iface, delay)
+    }
+}
+
+/// A step of an initialisation sequence, see [`DisplayDefinition::INIT`]
+#[derive(Debug, Copy, Clone)]
+pub enum InitStep {
+    /// Send the command
+    Command(Command),
+    /// Wait for the given number of milliseconds
+    DelayMs(u16),
+}
+
+/// Run the initialisation `steps` in order
+///
+/// # Errors
+///
+/// This method may return an error if there are communication issues with the display.
+pub fn run_init(
+    steps: &[InitStep],
+    iface: &mut impl WriteOnlyDataCommand,
+    delay: &mut impl DelayNs,
+) -> Result<(), DisplayError> {
+    for step in steps {
+        match *step {
+            InitStep::Command(command) => command.send(iface)?,
+            InitStep::DelayMs(ms) => delay.delay_ms(ms.into()),
+        }
+    }
+
+    Ok(())
 }
 
 /// Screen Definition
@@ -55,112 +108,230 @@ impl DisplayDefinition for DisplayResolution240x240 {
     const WIDTH: u16 = 240;
     const HEIGHT: u16 = 240;
 
+    const VISIBLE_INSETS: Option<&'static [u16]> = Some(&round_insets::<240>());
+
+    type Buffer = [u16; Self::WIDTH as usize * Self::HEIGHT as usize];
+}
+
+/// Screen Definition
+/// Resolution 240 x 280
+///
+/// Rounded rectangle modules, showing rows 20 to 299 of a 240 x 320 frame memory.
+#[derive(Debug, Copy, Clone)]
+pub struct DisplayResolution240x280;
+
+impl DisplayDefinition for DisplayResolution240x280 {
+    const WIDTH: u16 = 240;
+    const HEIGHT: u16 = 280;
+
+    const OFFSET_Y: u16 = 20;
+
+    const ROWS: u16 = 320;
+
+    type Buffer = [u16; Self::WIDTH as usize * Self::HEIGHT as usize];
+}
+
+/// Screen Definition
+/// Resolution 240 x 320
+#[derive(Debug, Copy, Clone)]
+pub struct DisplayResolution240x320;
+
+impl DisplayDefinition for DisplayResolution240x320 {
+    const WIDTH: u16 = 240;
+    const HEIGHT: u16 = 320;
+
+    const ROWS: u16 = 320;
+
     type Buffer = [u16; Self::WIDTH as usize * Self::HEIGHT as usize];
+}
+
+/// Screen Definition
+/// Resolution 128 x 128
+///
+/// Small modules whose controller frame memory is exactly the visible area.
+#[derive(Debug, Copy, Clone)]
+pub struct DisplayResolution128x128;
+
+impl DisplayDefinition for DisplayResolution128x128 {
+    const WIDTH: u16 = 128;
+    const HEIGHT: u16 = 128;
+
+    const COLS: u16 = 128;
+    const ROWS: u16 = 128;
+
+    type Buffer = [u16; Self::WIDTH as usize * Self::HEIGHT as usize];
+}
+
+/// Screen Definition
+/// Resolution `W` x `H`, with the active area starting at column `OX` and row `OY`
+///
+/// This covers panels with odd offsets without writing a whole [`DisplayDefinition`]. Stable
+/// Rust can't size an array from `W * H`, so the framebuffer length is given as `N`, which
+/// must equal `W * H` (checked at compile time):
+///
+/// ```ignore
+/// let display = Gc9a01::new(
+///     interface,
+///     DisplayResolutionGeneric::<240, 240, 0, 20, { 240 * 240 }>,
+///     DisplayRotation::Rotate0,
+/// );
+/// ```
+///
+/// The controller memory is assumed to be the smallest one holding the active area, at least
+/// 240 x 240. Rotations mirror the offsets within that memory, so implement
+/// [`DisplayDefinition`] with the real `COLS` and `ROWS` when the panel memory is larger.
+#[derive(Debug, Copy, Clone)]
+pub struct DisplayResolutionGeneric<
+    const W: u16,
+    const H: u16,
+    const OX: u16,
+    const OY: u16,
+    const N: usize,
+>;
+
+impl<const W: u16, const H: u16, const OX: u16, const OY: u16, const N: usize> DisplayDefinition
+    for DisplayResolutionGeneric<W, H, OX, OY, N>
+{
+    const WIDTH: u16 = W;
+    const HEIGHT: u16 = H;
+
+    const OFFSET_X: u16 = OX;
+    const OFFSET_Y: u16 = OY;
+
+    const COLS: u16 = if W + OX > 240 { W + OX } else { 240 };
+    const ROWS: u16 = if H + OY > 240 { H + OY } else { 240 };
+
+    type Buffer = [u16; N];
 
     fn configure(
         &self,
         iface: &mut impl WriteOnlyDataCommand,
         delay: &mut impl DelayNs,
     ) -> Result<(), DisplayError> {
-        Command::InnerRegisterEnable1.send(iface)?;
-        Command::InnerRegisterEnable2.send(iface)?;
-
-        Command::DispalyFunctionControl(GSMode::G1toG32, SSMode::S1toS360, 0, 0).send(iface)?;
-
-        Command::MemoryAccessControl(
-            Logical::Off,
-            Logical::Off,
-            Logical::Off,
-            Logical::On,
-            Logical::On,
-            Logical::Off,
-        )
-        .send(iface)?;
-
-        // maybe an issue
-        Command::PixelFormatSet(Dbi::Pixel16bits, Dpi::Pixel16bits).send(iface)?;
-
-        // c3
-        Command::Vreg1aVoltageControl(0x13).send(iface)?;
-        // c4
-        Command::Vreg1bVoltageControl(0x13).send(iface)?;
-        // c9
-        Command::Vreg2aVoltageControl(0x22).send(iface)?;
-
-        // gamma
-        Command::SetGamma1(Gamma1 {
-            dig2j0_n: 0b1,
-            vr1_n: 0b00_0101,
-            dig2j1_n: 0b0,
-            vr2_n: 0b00_1001,
-            vr4_n: 0b1000,
-            vr6_n: 0b1000,
-            vr0_n: 0b10,
-            vr13_n: 0b0110,
-            vr20_n: 0b10_1010,
-        })
-        .send(iface)?;
-
-        Command::SetGamma2(Gamma2 {
-            vr43_n: 0b100_0011,
-            vr27_n: 0b11,
-            vr57_n: 0b1_0000,
-            vr36_n: 0b11,
-            vr59_n: 0b1_0010,
-            vr61_n: 0b11_0110,
-            vr62_n: 0b11_0111,
-            vr50_n: 0b110,
-            vr63_n: 0b1111,
-        })
-        .send(iface)?;
-
-        // possible issue here
-        Command::SetGamma3(Gamma3 {
-            dig2j0_p: 0b1,
-            vr1_p: 0b00_0101,
-            dig2j1_p: 0b0,
-            vr2_p: 0b00_1001,
-            vr4_p: 0b1000,
-            vr6_p: 0b1000,
-            vr0_p: 0b10,
-            vr13_p: 0b0110,
-            vr20_p: 0b10_1010,
-        })
-        .send(iface)?;
-
-        Command::SetGamma4(Gamma4 {
-            vr43_p: 0b100_0011,
-            vr27_p: 0b11,
-            vr57_p: 0b1_0000,
-            vr36_p: 0b11,
-            vr59_p: 0b1_0010,
-            vr61_p: 0b11_0110,
-            vr62_p: 0b11_0111,
-            vr50_p: 0b110,
-            vr63_p: 0b1111,
-        })
-        .send(iface)?;
-
-        // frame
-        Command::FrameRate(DINVMode::Inversion8Dot).send(iface)?;
-
-        Command::DisplayInversion(Logical::On).send(iface)?;
-
-        // undocumented stuff here
-        Command::SetUndocumented066h.send(iface)?;
-        Command::SetUndocumented067h.send(iface)?;
-        Command::SetUndocumented074h.send(iface)?;
-        Command::SetUndocumented098h.send(iface)?;
-
-        Command::TearingEffectLine(Logical::On).send(iface)?;
-        Command::DisplayInversion(Logical::On).send(iface)?;
-        Command::SleepMode(Logical::Off).send(iface)?;
-        delay.delay_ms(120);
-
-        Ok(())
+        const {
+            assert!(
+                N == W as usize * H as usize,
+                "the buffer length N must be W * H"
+            );
+        }
+
+        run_init(Self::INIT, iface, delay)
     }
 }
 
+/// Initialisation sequence shared by every resolution, the default [`DisplayDefinition::INIT`]
+pub const DEFAULT_INIT: &[InitStep] = &[
+    InitStep::Command(Command::InnerRegisterEnable1),
+    InitStep::Command(Command::InnerRegisterEnable2),
+    InitStep::Command(Command::DispalyFunctionControl(
+        GSMode::G1toG32,
+        SSMode::S1toS360,
+        0,
+        0,
+    )),
+    InitStep::Command(
+        MadctlBuilder::new()
+            .vertical_refresh(Logical::On)
+            .bgr(Logical::On)
+            .build(),
+    ),
+    // maybe an issue
+    InitStep::Command(Command::PixelFormatSet(Dbi::Pixel16bits, Dpi::Pixel16bits)),
+    // c3
+    InitStep::Command(Command::Vreg1aVoltageControl(0x13)),
+    // c4
+    InitStep::Command(Command::Vreg1bVoltageControl(0x13)),
+    // c9
+    InitStep::Command(Command::Vreg2aVoltageControl(0x22)),
+    // gamma
+    InitStep::Command(Command::SetGamma1(Gamma1 {
+        dig2j0_n: 0b1,
+        vr1_n: 0b00_0101,
+        dig2j1_n: 0b0,
+        vr2_n: 0b00_1001,
+        vr4_n: 0b1000,
+        vr6_n: 0b1000,
+        vr0_n: 0b10,
+        vr13_n: 0b0110,
+        vr20_n: 0b10_1010,
+    })),
+    InitStep::Command(Command::SetGamma2(Gamma2 {
+        vr43_n: 0b100_0011,
+        vr27_n: 0b11,
+        vr57_n: 0b1_0000,
+        vr36_n: 0b11,
+        vr59_n: 0b1_0010,
+        vr61_n: 0b11_0110,
+        vr62_n: 0b11_0111,
+        vr50_n: 0b110,
+        vr63_n: 0b1111,
+    })),
+    // possible issue here
+    InitStep::Command(Command::SetGamma3(Gamma3 {
+        dig2j0_p: 0b1,
+        vr1_p: 0b00_0101,
+        dig2j1_p: 0b0,
+        vr2_p: 0b00_1001,
+        vr4_p: 0b1000,
+        vr6_p: 0b1000,
+        vr0_p: 0b10,
+        vr13_p: 0b0110,
+        vr20_p: 0b10_1010,
+    })),
+    InitStep::Command(Command::SetGamma4(Gamma4 {
+        vr43_p: 0b100_0011,
+        vr27_p: 0b11,
+        vr57_p: 0b1_0000,
+        vr36_p: 0b11,
+        vr59_p: 0b1_0010,
+        vr61_p: 0b11_0110,
+        vr62_p: 0b11_0111,
+        vr50_p: 0b110,
+        vr63_p: 0b1111,
+    })),
+    // frame
+    InitStep::Command(Command::FrameRate(DINVMode::Inversion8Dot, 0, 0)),
+    InitStep::Command(Command::DisplayInversion(Logical::On)),
+    // undocumented stuff here
+    InitStep::Command(Command::SetUndocumented066h),
+    InitStep::Command(Command::SetUndocumented067h),
+    InitStep::Command(Command::SetUndocumented074h),
+    InitStep::Command(Command::SetUndocumented098h),
+    InitStep::Command(Command::TearingEffectLine(Logical::On)),
+    InitStep::Command(Command::DisplayInversion(Logical::On)),
+    InitStep::Command(Command::SleepMode(Logical::Off)),
+    InitStep::DelayMs(120),
+];
+
+/// Compute the visible chord of a round panel of diameter `N`.
+///
+/// Entry `y` is the number of pixels hidden at each end of row `y`. A pixel is considered
+/// visible as soon as any part of it lies inside the inscribed circle.
+#[must_use]
+pub const fn round_insets<const N: usize>() -> [u16; N] {
+    const fn isqrt(value: u32) -> u32 {
+        let mut root = 0u32;
+        while (root + 1) * (root + 1) <= value {
+            root += 1;
+        }
+        root
+    }
+
+    let mut insets = [0u16; N];
+    // Work in half pixels so the circle center falls on an integer coordinate
+    let diameter = N as u32;
+    let mut row = 0;
+    while row < N {
+        let center_dist = (2 * row as u32 + 1).abs_diff(diameter);
+        let edge_dist = center_dist.saturating_sub(1);
+        let half_chord = isqrt(diameter * diameter - edge_dist * edge_dist);
+        insets[row] = (diameter.saturating_sub(half_chord + 1) / 2) as u16;
+        row += 1;
+    }
+
+    insets
+}
+
 pub trait NewZeroed {
     /// Creates a new value with its memory set to zero
     fn new_zeroed() -> Self;
@@ -1,13 +1,31 @@
-use super::brightness::Brightness;
-use super::command::{Command, Logical};
+use super::brightness::{Brightness, ZeroBrightnessPolicy};
+use super::command::{
+    Command, DINVMode, Dbi, Dpi, Gamma1, Gamma2, Gamma3, Gamma4, Logical, MadctlBuilder, TEPolarity,
+};
 use super::display::DisplayDefinition;
-use super::mode::{BasicMode, BufferedGraphics};
+use super::gamma::GammaBuilder;
+use super::mode::BufferedGraphics;
 use super::rotation::DisplayRotation;
+use super::spi::ReadableInterface;
 
 use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+
+#[cfg(feature = "async")]
+use display_interface::AsyncWriteOnlyDataCommand;
 use embedded_hal::delay::DelayNs;
 use embedded_hal::digital::OutputPin;
 
+#[cfg(feature = "graphics")]
+use super::pixel::PixelByteFormat;
+#[cfg(feature = "graphics")]
+use embedded_graphics_core::{
+    geometry::{Point, Size},
+    primitives::Rectangle,
+};
+
+/// Words staged per slice write when sending framebuffer pixels
+pub(crate) const PIXEL_SCRATCH_LEN: usize = 128;
+
 /// Gc9a01 Driver
 pub struct Gc9a01<I, D, M>
 where
@@ -18,6 +36,18 @@ where
     pub(crate) display: D,
     pub(crate) mode: M,
     pub(crate) display_rotation: DisplayRotation,
+    pub(crate) mirror: (bool, bool),
+    pub(crate) bgr: Logical,
+    pub(crate) pixel_format: Dbi,
+    pub(crate) offset: (u16, u16),
+    pub(crate) zero_brightness_policy: ZeroBrightnessPolicy,
+    pub(crate) brightness: Brightness,
+    pub(crate) blanked: bool,
+    pub(crate) inverted: bool,
+    pub(crate) suspended: Logical,
+    pub(crate) display_control: Option<(bool, bool, bool)>,
+    pub(crate) draw_area: Option<((u16, u16), (u16, u16))>,
+    pub(crate) initialized: bool,
 }
 
 impl<I, D, M> Gc9a01<I, D, M>
@@ -27,56 +57,132 @@ where
 {
     /// Reset the display.
     ///
+    /// The controller reverts to its power-on defaults, so the driver forgets what it cached
+    /// about the hardware state and reports itself as not initialised: the screen has to be
+    /// initialised again before use. The requested rotation is kept and re-applied by the
+    /// next init.
+    ///
     /// # Errors
     ///
     /// See `OutputPin` definition for more information.
-    #[allow(clippy::needless_pass_by_ref_mut)]
     pub fn reset<RST, DELAY>(&mut self, rst: &mut RST, delay: &mut DELAY) -> Result<(), RST::Error>
     where
         RST: OutputPin,
         DELAY: DelayNs,
     {
-        fn inner_reset<RST, DELAY>(rst: &mut RST, delay: &mut DELAY) -> Result<(), RST::Error>
-        where
-            RST: OutputPin,
-            DELAY: DelayNs,
-        {
-            rst.set_high()?;
-            delay.delay_ms(50);
-            rst.set_low()?;
-            delay.delay_ms(50);
-            rst.set_high()?;
-            delay.delay_ms(50);
-            Ok(())
-        }
+        self.reset_with_timing(rst, delay, 50, 50, 50)
+    }
+
+    /// Reset the display with a custom pulse, see [`reset`](Self::reset).
+    ///
+    /// RST is held high for `high_ms`, pulled low for `low_ms`, then released and the driver
+    /// waits `post_ms` before returning. `reset` uses 50ms for each step.
+    ///
+    /// # Errors
+    ///
+    /// See `OutputPin` definition for more information.
+    pub fn reset_with_timing<RST, DELAY>(
+        &mut self,
+        rst: &mut RST,
+        delay: &mut DELAY,
+        high_ms: u32,
+        low_ms: u32,
+        post_ms: u32,
+    ) -> Result<(), RST::Error>
+    where
+        RST: OutputPin,
+        DELAY: DelayNs,
+    {
+        self.forget_hardware_state();
 
-        inner_reset(rst, delay)
+        rst.set_high()?;
+        delay.delay_ms(high_ms);
+        rst.set_low()?;
+        delay.delay_ms(low_ms);
+        rst.set_high()?;
+        delay.delay_ms(post_ms);
+
+        Ok(())
     }
-}
 
-impl<I, D> Gc9a01<I, D, BasicMode>
-where
-    I: WriteOnlyDataCommand,
-    D: DisplayDefinition,
-{
-    /// Create a basic [`Gc9a01`] interface.
+    /// Reset the display through the Software Reset command (01h), for boards without a RST
+    /// line.
+    ///
+    /// Registers return to their reset defaults like with [`reset`](Self::reset), so the
+    /// screen has to be initialised again before use: `init()` can directly follow this call.
+    /// It waits 120ms, after which the controller accepts the Sleep Out of the init sequence.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn soft_reset(&mut self, delay: &mut impl DelayNs) -> Result<(), DisplayError> {
+        self.forget_hardware_state();
+
+        Command::SoftwareReset.send(&mut self.interface)?;
+        delay.delay_ms(120);
+
+        Ok(())
+    }
+
+    /// Read the display identification (04h): manufacturer ID, module version ID and module ID
+    ///
+    /// Useful at bring-up to check the controller answers at all. This requires a
+    /// [`ReadableInterface`] with the panel SDO line wired, see its documentation.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn read_display_id(&mut self) -> Result<[u8; 3], DisplayError>
+    where
+        I: ReadableInterface,
+    {
+        self.read_after_dummy(0x04)
+    }
+
+    /// Read the display status (09h), most significant byte first
+    ///
+    /// It reports the booster, the MADCTL flags, the pixel format and the sleep, display and
+    /// inversion states. Like [`read_display_id`](Self::read_display_id) this requires a
+    /// [`ReadableInterface`] with the panel SDO line wired.
+    ///
+    /// # Errors
     ///
-    /// Use the `into_buffed_graphics` methods to enable more functionality.
-    pub fn new(interface: I, screen: D, screen_rotation: DisplayRotation) -> Self {
-        Self {
-            interface,
-            display: screen,
-            mode: BasicMode::new(),
-            display_rotation: screen_rotation,
+    /// This method may return an error if there are communication issues with the display.
+    pub fn read_status(&mut self) -> Result<[u8; 4], DisplayError>
+    where
+        I: ReadableInterface,
+    {
+        self.read_after_dummy(0x09)
+    }
+
+    /// Read the `N` bytes answered to `command`, which start after one dummy clock cycle.
+    fn read_after_dummy<const N: usize>(&mut self, command: u8) -> Result<[u8; N], DisplayError>
+    where
+        I: ReadableInterface,
+    {
+        // one more byte to hold the bit shifted out by the dummy cycle
+        let mut raw = [0u8; 5];
+        let raw = raw.get_mut(..=N).ok_or(DisplayError::OutOfBoundsError)?;
+        self.interface.read_data(command, raw)?;
+
+        let mut data = [0u8; N];
+        for (byte, pair) in data.iter_mut().zip(raw.windows(2)) {
+            *byte = (pair[0] << 1) | (pair[1] >> 7);
         }
+        Ok(data)
+    }
+
+    /// The hardware is back to its power-on state, drop what was cached about it.
+    const fn forget_hardware_state(&mut self) {
+        self.draw_area = None;
+        self.brightness = Brightness::DIMMEST;
+        self.blanked = false;
+        self.inverted = false;
+        self.suspended = Logical::Off;
+        self.display_control = None;
+        self.initialized = false;
     }
-}
 
-impl<I, D, M> Gc9a01<I, D, M>
-where
-    I: WriteOnlyDataCommand,
-    D: DisplayDefinition,
-{
     /// Convert the display into another interface mode.
     fn into_mode<MODE>(self, mode: MODE) -> Gc9a01<I, D, MODE> {
         Gc9a01 {
@@ -84,6 +190,18 @@ where
             interface: self.interface,
             display: self.display,
             display_rotation: self.display_rotation,
+            mirror: self.mirror,
+            bgr: self.bgr,
+            pixel_format: self.pixel_format,
+            offset: self.offset,
+            zero_brightness_policy: self.zero_brightness_policy,
+            brightness: self.brightness,
+            blanked: self.blanked,
+            inverted: self.inverted,
+            suspended: self.suspended,
+            display_control: self.display_control,
+            draw_area: self.draw_area,
+            initialized: self.initialized,
         }
     }
 
@@ -104,18 +222,153 @@ where
         // TODO: implement initialization sequence
 
         let rotation = self.display_rotation;
+        self.draw_area = None;
 
         // Dedicated/Custom implementation override
         self.display.configure(&mut self.interface, delay)?;
 
-        // Enforced context parameters
+        // the init sequences select 16-bit pixels and leave the inversion on
+        self.pixel_format = Dbi::Pixel16bits;
+        self.inverted = true;
+
+        self.finish_init(rotation, delay)
+    }
+
+    /// Initialise the screen from a raw initialisation table instead of the
+    /// [`DisplayDefinition::configure`] sequence.
+    ///
+    /// Each entry is `(command, parameters, delay_ms)`: the command byte is sent, followed by
+    /// its parameters (if any), then the driver waits `delay_ms` milliseconds. This is the
+    /// format most vendor and Arduino init sequences are published in, so they can be used
+    /// verbatim. The rotation, brightness and display-on steps of the regular init are
+    /// applied after the table.
+    ///
+    /// The driver can't tell what the table selects, so give the `pixel_format` (3Ah) and the
+    /// inversion (21h/20h) it leaves the panel in: `true` if it ends with `inverted` pixels.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn init_from_table(
+        &mut self,
+        table: &[(u8, &[u8], u16)],
+        pixel_format: Dbi,
+        inverted: bool,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), DisplayError> {
+        let rotation = self.display_rotation;
+        self.draw_area = None;
+
+        for &(command, params, delay_ms) in table {
+            self.send_raw(command, params)?;
+            if delay_ms > 0 {
+                delay.delay_ms(delay_ms.into());
+            }
+        }
+
+        self.pixel_format = pixel_format;
+        self.inverted = inverted;
+
+        self.finish_init(rotation, delay)
+    }
+
+    /// Send an arbitrary command byte followed by its parameters (if any), e.g. to poke
+    /// undocumented registers during bring-up.
+    ///
+    /// The driver can't tell what the command changes, so it forgets the cached draw window.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn send_raw(&mut self, cmd: u8, params: &[u8]) -> Result<(), DisplayError> {
+        self.draw_area = None;
+
+        self.interface.send_commands(DataFormat::U8(&[cmd]))?;
+        if !params.is_empty() {
+            self.interface.send_data(DataFormat::U8(params))?;
+        }
+
+        Ok(())
+    }
+
+    /// Send `cmds` in order, e.g. a gamma or power preset.
+    ///
+    /// Like [`send_raw`](Self::send_raw), the cached draw window is forgotten. Each command
+    /// keeps its own transfers, since the DC line toggles between a command and its
+    /// parameters: use an interface owning the chip select, like [`SPIBusInterface`], to
+    /// avoid locking an `SpiDevice` twice per command.
+    ///
+    /// [`SPIBusInterface`]: crate::SPIBusInterface
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    /// The commands following a failed one are not sent.
+    pub fn send_sequence(&mut self, cmds: &[Command]) -> Result<(), DisplayError> {
+        self.draw_area = None;
+
+        for &cmd in cmds {
+            cmd.send(&mut self.interface)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reset and initialise the screen `retries + 1` times, working around modules that
+    /// ignore the first initialisation after a cold boot.
+    ///
+    /// An ignored initialisation still succeeds on the bus, so an `Ok` attempt does not end
+    /// the loop: every retry always runs. Errors of the earlier attempts are ignored, the
+    /// result is the one of the last attempt. This is a blind retry, for write-only
+    /// interfaces: with a [`ReadableInterface`], check [`read_status`](Self::read_status)
+    /// after [`init_with_addr_mode`](Self::init_with_addr_mode) instead, and initialise
+    /// again only if the panel is still asleep.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::RSError`] if the reset pin fails, or another error
+    /// if there are communication issues with the display during the last attempt.
+    pub fn init_robust<RST, DELAY>(
+        &mut self,
+        rst: &mut RST,
+        delay: &mut DELAY,
+        retries: u8,
+    ) -> Result<(), DisplayError>
+    where
+        RST: OutputPin,
+        DELAY: DelayNs,
+    {
+        let mut result = Ok(());
+
+        for _attempt in 0..=retries {
+            result = self
+                .reset(rst, delay)
+                .map_err(|_reset| DisplayError::RSError)
+                .and_then(|()| self.init_with_addr_mode(delay));
+        }
+
+        result
+    }
+
+    /// Enforced context parameters applied once the panel is configured
+    fn finish_init(
+        &mut self,
+        rotation: DisplayRotation,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), DisplayError> {
+        // the init sequences leave sleep mode off
+        self.suspended = Logical::Off;
+
         self.set_display_rotation(rotation)?;
+        // enables the brightness control block first, the panel ignores 51h until then
         self.set_brightness(Brightness::default())?;
 
         // Command::MemoryAddressingMode(mode).send(&mut self.interface)?;
         Command::DisplayState(Logical::On).send(&mut self.interface)?;
         delay.delay_ms(120);
 
+        self.initialized = true;
+
         Ok(())
     }
 
@@ -160,6 +413,7 @@ where
     ) -> Result<(), DisplayError> {
         Self::flush_buffer_chunks(
             &mut self.interface,
+            self.pixel_format,
             buffer,
             disp_width,
             upper_left,
@@ -195,21 +449,24 @@ where
         // Allocate a zeroed buffer on the stack
         let stack_alloc = [0; CLEAR_SIZE_STACK];
 
-        // Get the width and height of the display
-        let (width, height) = self.bounds();
-        let total_size = (width * height) as usize;
-
-        // Calculate how many chunks of size CLEAR_SIZE_STACK are needed
-        let mut total_it = total_size / CLEAR_SIZE_STACK;
+        // Every pixel of the active area, whatever the rotation
+        let total_size = D::PIXELS;
 
-        // Set the draw area to the entire screen
-        self.set_draw_area((0, 0), (width, height))?;
+        self.set_full_window()?;
+        self.set_write_mode()?;
 
         // Send the zeroed buffer in chunks until the entire screen is cleared
-        while total_it > 1 {
+        for _chunk in 0..total_size / CLEAR_SIZE_STACK {
             self.interface
                 .send_data(DataFormat::U16BEIter(&mut stack_alloc.iter().copied()))?;
-            total_it -= 1;
+        }
+
+        // and the pixels left over by the last full chunk
+        let remainder = total_size % CLEAR_SIZE_STACK;
+        if remainder > 0 {
+            self.interface.send_data(DataFormat::U16BEIter(
+                &mut stack_alloc[..remainder].iter().copied(),
+            ))?;
         }
 
         Ok(())
@@ -240,163 +497,1266 @@ where
 
     /// Set the screen rotation.
     ///
+    /// The mirroring set by [`set_mirror`](Self::set_mirror) is kept.
+    ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
-    #[allow(clippy::match_same_arms)]
     pub fn set_display_rotation(&mut self, rotation: DisplayRotation) -> Result<(), DisplayError> {
         self.display_rotation = rotation;
+        // the window is interpreted differently once MADCTL changes
+        self.draw_area = None;
 
-        match self.display_rotation {
-            DisplayRotation::Rotate0 => Command::MemoryAccessControl(
-                Logical::Off,
-                Logical::Off,
-                Logical::Off,
-                Logical::On,
-                Logical::On,
-                Logical::Off,
-            )
-            .send(&mut self.interface)?,
-            DisplayRotation::Rotate90 => Command::MemoryAccessControl(
-                Logical::On,
-                Logical::Off,
-                Logical::Off,
-                Logical::On,
-                Logical::On,
-                Logical::Off,
-            )
-            .send(&mut self.interface)?,
-            DisplayRotation::Rotate180 => Command::MemoryAccessControl(
-                Logical::On,
-                Logical::On,
-                Logical::Off,
-                Logical::On,
-                Logical::On,
-                Logical::Off,
-            )
-            .send(&mut self.interface)?,
-            DisplayRotation::Rotate270 => Command::MemoryAccessControl(
-                Logical::Off,
-                Logical::On,
-                Logical::Off,
-                Logical::On,
-                Logical::On,
-                Logical::Off,
-            )
-            .send(&mut self.interface)?,
-        };
+        let (my, mx) = self.address_order();
+        MadctlBuilder::new()
+            .row_order(my.into())
+            .col_order(mx.into())
+            .vertical_refresh(Logical::On)
+            .bgr(self.bgr)
+            .build()
+            .send(&mut self.interface)
+    }
+
+    /// Set the color order of the panel filter: BGR when `bgr` is set, RGB otherwise.
+    ///
+    /// Swap it when red and blue come out exchanged. The rotation and mirroring are kept,
+    /// and the controller applies the new order immediately: the frame memory does not have
+    /// to be sent again. The driver keeps the order, across a reset too, and every init
+    /// sends it over the BGR bit of its init table: [`DisplayDefinition::DEFAULT_BGR`] only
+    /// seeds the order of a new driver.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn set_color_order(&mut self, bgr: bool) -> Result<(), DisplayError> {
+        self.bgr = bgr.into();
+        self.set_display_rotation(self.display_rotation)
+    }
+
+    /// Select the pixel format (3Ah) of the MCU (`dbi`) and RGB (`dpi`) interfaces.
+    ///
+    /// The `BufferedGraphics` framebuffer stays Rgb565, its flushes repack the pixels for
+    /// `dbi` on the way: 12-bit packs two pixels into three bytes, nearly halving the traffic
+    /// of 16-bit, and 18-bit sends one byte per channel. Every other pixel write (basic mode,
+    /// `flush_with`) sends Rgb565, so switch back to 16-bit before using them.
+    /// Init selects 16-bit again.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn set_pixel_format(&mut self, dbi: Dbi, dpi: Dpi) -> Result<(), DisplayError> {
+        Command::PixelFormatSet(dbi, dpi).send(&mut self.interface)?;
+        self.pixel_format = dbi;
 
         Ok(())
     }
 
-    /// Change the display brightness.
+    /// Raise the tearing effect output when the panel scan reaches `line` (44h), e.g. to
+    /// start a transfer mid-frame.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if `line` is not below
+    /// `D::ROWS`, and may return an error if there are communication issues with the display.
+    pub fn set_tear_scanline(&mut self, line: u16) -> Result<(), DisplayError> {
+        if line >= D::ROWS {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        Command::SetTearScanline(line).send(&mut self.interface)
+    }
+
+    /// Configure the tearing effect output in one call.
+    ///
+    /// [`Command::TEControl`] is ignored while the output is on, so the output is first turned off
+    /// (34h). When `enable` is set, the pulse `polarity` is then written, the output turned
+    /// back on (35h) and, with `Some(line)`, moved to that scan line (44h) as with
+    /// [`set_tear_scanline`](Self::set_tear_scanline).
+    ///
+    /// While the panel sleeps with the output on, the TE pin stays low.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if `scanline` is not below
+    /// `D::ROWS`, before anything is sent, and may return an error if there are communication
+    /// issues with the display.
+    pub fn configure_tearing(
+        &mut self,
+        enable: bool,
+        polarity: TEPolarity,
+        scanline: Option<u16>,
+    ) -> Result<(), DisplayError> {
+        if scanline.is_some_and(|line| line >= D::ROWS) {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        Command::TearingEffectLine(Logical::Off).send(&mut self.interface)?;
+        if !enable {
+            return Ok(());
+        }
+
+        Command::TEControl(polarity, 0).send(&mut self.interface)?;
+        Command::TearingEffectLine(Logical::On).send(&mut self.interface)?;
+        if let Some(line) = scanline {
+            Command::SetTearScanline(line).send(&mut self.interface)?;
+        }
+
+        Ok(())
+    }
+
+    /// Pixel format of the MCU interface, see [`set_pixel_format`](Self::set_pixel_format)
+    pub const fn pixel_format(&self) -> Dbi {
+        self.pixel_format
+    }
+
+    /// Mirror the image horizontally and/or vertically, on top of the rotation.
+    ///
+    /// Both directions are in display coordinates, whatever the rotation: e.g. for a panel
+    /// seen through a mirror or a prism. The flags are kept across rotation changes and
+    /// re-applied by the next init.
     ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
-    pub fn set_brightness(&mut self, brightness: Brightness) -> Result<(), DisplayError> {
-        Command::DisplayBrightness(brightness.brightness).send(&mut self.interface)
+    pub fn set_mirror(&mut self, horizontal: bool, vertical: bool) -> Result<(), DisplayError> {
+        self.mirror = (horizontal, vertical);
+        self.set_display_rotation(self.display_rotation)
     }
 
-    /// Set hardware screen state
+    /// Address order bits (`MY`, `MX`) of MADCTL for the current rotation and mirroring
+    ///
+    /// On this panel `MY` reverses the columns and `MX` the rows.
+    const fn address_order(&self) -> (bool, bool) {
+        let (my, mx, transposed) = match self.display_rotation {
+            DisplayRotation::Rotate0 => (false, false, false),
+            DisplayRotation::Rotate90 => (true, false, true),
+            DisplayRotation::Rotate180 => (true, true, false),
+            DisplayRotation::Rotate270 => (false, true, true),
+        };
+
+        // display x runs along the panel rows once transposed
+        let (horizontal, vertical) = self.mirror;
+        if transposed {
+            (my ^ vertical, mx ^ horizontal)
+        } else {
+            (my ^ horizontal, mx ^ vertical)
+        }
+    }
+
+    /// Change the gamma curve from raw gamma registers.
+    ///
+    /// The inner registers are enabled (FEh/EFh) first, so this can be called at any time
+    /// after init, e.g. to tune a panel batch live.
     ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
-    pub fn set_screen_state(&mut self, on: Logical) -> Result<(), DisplayError> {
-        Command::DisplayState(on).send(&mut self.interface)
+    pub fn set_gamma(
+        &mut self,
+        g1: Gamma1,
+        g2: Gamma2,
+        g3: Gamma3,
+        g4: Gamma4,
+    ) -> Result<(), DisplayError> {
+        Command::InnerRegisterEnable1.send(&mut self.interface)?;
+        Command::InnerRegisterEnable2.send(&mut self.interface)?;
+
+        Command::SetGamma1(g1).send(&mut self.interface)?;
+        Command::SetGamma2(g2).send(&mut self.interface)?;
+        Command::SetGamma3(g3).send(&mut self.interface)?;
+        Command::SetGamma4(g4).send(&mut self.interface)
     }
 
-    /// Set hardware to inverse the GDDRAM framebuffer output
+    /// Change the refresh rate through the RTN1 and RTN2 line period dividers of Frame Rate
+    /// (E8h), keeping the 8 dot inversion of the init sequence.
+    ///
+    /// Larger dividers lower the refresh rate. See [`Command::FrameRate`]: the values are
+    /// found by measuring the TE period. The inner registers are enabled (FEh/EFh) first.
     ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
-    pub fn set_invert_pixels(&mut self, value: bool) -> Result<(), DisplayError> {
-        Command::DisplayInversion(value.into()).send(&mut self.interface)
+    pub fn set_frame_rate(&mut self, rtn1: u8, rtn2: u8) -> Result<(), DisplayError> {
+        Command::InnerRegisterEnable1.send(&mut self.interface)?;
+        Command::InnerRegisterEnable2.send(&mut self.interface)?;
+
+        Command::FrameRate(DINVMode::Inversion8Dot, rtn1, rtn2).send(&mut self.interface)
     }
 
-    /// Set hardware framebuffer to configure a limited area
-    /// of the screen where any pixel should be draw.
+    /// Change the charge pump clocks of [`Command::ChargePumpFrequentControl`], e.g. to raise
+    /// them on a panel that flickers in the cold.
     ///
-    /// * (`x_start`, `y_start`) - starting point
-    /// * (`x_end`, `y_end`) - ending point
+    /// `avdd`, `avee` and `vcl` are 3-bit fields, `vgh` and `vgl` 4-bit ones: higher bits are
+    /// dropped. The inner registers are enabled (FEh/EFh) first.
     ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
-    pub fn set_draw_area(
+    pub fn set_charge_pump(
         &mut self,
-        start: (u16, u16),
-        end: (u16, u16),
+        avdd: u8,
+        avee: u8,
+        vcl: u8,
+        vgh: u8,
+        vgl: u8,
     ) -> Result<(), DisplayError> {
-        Command::ColumnAddressSet(start.0, end.0).send(&mut self.interface)?;
-        Command::RowAddressSet(start.1, end.1).send(&mut self.interface)?;
+        Command::InnerRegisterEnable1.send(&mut self.interface)?;
+        Command::InnerRegisterEnable2.send(&mut self.interface)?;
 
-        Ok(())
+        Command::ChargePumpFrequentControl(avdd, avee, vcl, vgh, vgl).send(&mut self.interface)
     }
 
-    /// Set the hardware framebuffer to await incoming colors
+    /// Change the grayscale reference voltages: VREG1A (C3h), VREG1B (C4h) and VREG2A (C9h).
+    ///
+    /// The init sequences send `0x13`, `0x13` and `0x22`, see [`Command::Vreg1aVoltageControl`]
+    /// for how they map to voltages. The inner registers are enabled (FEh/EFh) first.
     ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
-    pub fn set_write_mode(&mut self) -> Result<(), DisplayError> {
-        Command::MemoryWrite.send(&mut self.interface)?;
-        Ok(())
+    pub fn set_vreg(&mut self, vreg1a: u8, vreg1b: u8, vreg2a: u8) -> Result<(), DisplayError> {
+        Command::InnerRegisterEnable1.send(&mut self.interface)?;
+        Command::InnerRegisterEnable2.send(&mut self.interface)?;
+
+        Command::Vreg1aVoltageControl(vreg1a).send(&mut self.interface)?;
+        Command::Vreg1bVoltageControl(vreg1b).send(&mut self.interface)?;
+        Command::Vreg2aVoltageControl(vreg2a).send(&mut self.interface)
     }
 
-    /// Get screen rotation
-    pub const fn get_screen_rotation(&self) -> DisplayRotation {
-        self.display_rotation
+    /// Change the gamma curve from a [`GammaBuilder`].
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn set_gamma_builder(&mut self, builder: GammaBuilder) -> Result<(), DisplayError> {
+        let (g1, g2, g3, g4) = builder.build();
+        self.set_gamma(g1, g2, g3, g4)
     }
 
-    /// Get pixel screen dimensions
-    pub const fn dimensions(&self) -> (u16, u16) {
-        match self.display_rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (D::WIDTH, D::HEIGHT),
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (D::HEIGHT, D::WIDTH),
+    /// Change the display brightness.
+    ///
+    /// With [`ZeroBrightnessPolicy::Blank`], a zero brightness also turns the display off
+    /// and the next non-zero brightness turns it back on.
+    ///
+    /// The brightness control block and the backlight are enabled first (53h) unless
+    /// [`set_display_control`](Self::set_display_control) was called since the last reset,
+    /// since the panel ignores the brightness without them.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn set_brightness(&mut self, brightness: Brightness) -> Result<(), DisplayError> {
+        let blank = self.zero_brightness_policy == ZeroBrightnessPolicy::Blank
+            && brightness.brightness == 0;
+
+        if self.blanked && !blank {
+            Command::DisplayState(Logical::On).send(&mut self.interface)?;
+            self.blanked = false;
         }
-    }
 
-    /// Get pixel screen bounds (x-1, y-1)
-    pub const fn bounds(&self) -> (u16, u16) {
-        match self.display_rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (D::WIDTH - 1, D::HEIGHT - 1),
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (D::HEIGHT - 1, D::WIDTH - 1),
+        if self.display_control.is_none() {
+            self.set_display_control(true, false, true)?;
+        }
+
+        Command::DisplayBrightness(brightness.brightness).send(&mut self.interface)?;
+
+        self.brightness = brightness;
+
+        if blank && !self.blanked {
+            Command::DisplayState(Logical::Off).send(&mut self.interface)?;
+            self.blanked = true;
         }
+
+        Ok(())
     }
 
-    /// Flush the buffer by chuncks
+    /// The brightness last sent by [`set_brightness`](Self::set_brightness) or the init.
+    ///
+    /// The value is tracked by the driver, not read from the panel. Before the first init it
+    /// is the power-on value, [`Brightness::DIMMEST`].
+    pub const fn current_brightness(&self) -> Brightness {
+        self.brightness
+    }
+
+    /// Walk the brightness linearly from the last value set to `target` in `steps` writes,
+    /// waiting `per_step_ms` milliseconds after each one.
+    ///
+    /// Every step goes through [`set_brightness`](Self::set_brightness), so the
+    /// [`ZeroBrightnessPolicy`] applies. With `0` steps `target` is set at once. See
+    /// [`set_brightness_smooth`](Self::set_brightness_smooth) for a ramp run by the
+    /// controller.
     ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
-    pub(crate) fn flush_buffer_chunks(
-        interface: &mut I,
-        buffer: &[u16],
-        disp_width: usize,
-        upper_left: (u16, u16),
-        lower_right: (u16, u16),
+    pub fn fade_brightness(
+        &mut self,
+        target: Brightness,
+        steps: u16,
+        delay: &mut impl DelayNs,
+        per_step_ms: u32,
     ) -> Result<(), DisplayError> {
-        Command::MemoryWrite.send(interface)?;
+        if steps == 0 {
+            return self.set_brightness(target);
+        }
 
-        // Number of rows to process (Y range)
-        let num_pages = (lower_right.1 - upper_left.1 + 1) as usize;
+        let start = i32::from(self.brightness.brightness);
+        let span = i32::from(target.brightness) - start;
 
-        // Starting row (Y coordinate)
-        let starting_page = upper_left.1 as usize;
+        for step in 1..=i32::from(steps) {
+            let value = start + span * step / i32::from(steps);
+            self.set_brightness(Brightness::custom(u8::try_from(value).unwrap_or_default()))?;
+            delay.delay_ms(per_step_ms);
+        }
 
-        // X coordinates (columns) for the rectangle
-        let page_lower = upper_left.0 as usize;
+        Ok(())
+    }
+
+    /// Change the display brightness with a smooth hardware ramp.
+    ///
+    /// The display dimming (DD) bit of Write CTRL Display (53h) makes the controller ramp
+    /// from the current brightness to every new 51h value by itself, so no MCU time is spent
+    /// stepping a fade. DD is enabled on the first call, along with the brightness control
+    /// block and the backlight, and stays enabled: later [`set_brightness`](Self::set_brightness)
+    /// calls ramp as well. The ramp duration depends on the internal oscillator and frame
+    /// rate, it is not configurable.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn set_brightness_smooth(&mut self, target: Brightness) -> Result<(), DisplayError> {
+        if self.display_control != Some((true, true, true)) {
+            self.set_display_control(true, true, true)?;
+        }
+
+        self.set_brightness(target)
+    }
+
+    /// Write CTRL Display (53h): enable the brightness control block (BCTRL), the display
+    /// dimming (DD) and the backlight control (BL).
+    ///
+    /// The panel ignores the brightness (51h) unless BCTRL is on. Dimming makes the controller
+    /// ramp to every new brightness instead of stepping, see
+    /// [`set_brightness_smooth`](Self::set_brightness_smooth).
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn set_display_control(
+        &mut self,
+        brightness_block: bool,
+        dimming: bool,
+        backlight: bool,
+    ) -> Result<(), DisplayError> {
+        Command::CtrlDisplay(brightness_block.into(), dimming.into(), backlight.into())
+            .send(&mut self.interface)?;
+        self.display_control = Some((brightness_block, dimming, backlight));
+
+        Ok(())
+    }
+
+    /// Set what [`set_brightness`](Self::set_brightness) does with a zero brightness.
+    ///
+    /// Takes effect on the next `set_brightness` call.
+    pub const fn set_zero_brightness_policy(&mut self, policy: ZeroBrightnessPolicy) {
+        self.zero_brightness_policy = policy;
+    }
+
+    /// Set hardware screen state
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn set_screen_state(&mut self, on: Logical) -> Result<(), DisplayError> {
+        Command::DisplayState(on).send(&mut self.interface)
+    }
+
+    /// Define the vertical scrolling area (33h): `tfa` fixed lines at the top, `vsa`
+    /// scrolling lines, then `bfa` fixed lines at the bottom.
+    ///
+    /// Lines are frame memory lines, and `tfa + vsa + bfa` must equal `D::ROWS`. Move the
+    /// scrolling area with [`scroll_to`](Self::scroll_to), e.g. to run a ticker without
+    /// redrawing it.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if the areas don't add up to
+    /// `D::ROWS`, or may return an error if there are communication issues with the display.
+    pub fn set_scroll_area(&mut self, tfa: u16, vsa: u16, bfa: u16) -> Result<(), DisplayError> {
+        if u32::from(tfa) + u32::from(vsa) + u32::from(bfa) != u32::from(D::ROWS) {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        Command::VertialScrollDef(tfa, vsa, bfa).send(&mut self.interface)
+    }
+
+    /// Show frame memory line `line` first in the scrolling area (37h), see
+    /// [`set_scroll_area`](Self::set_scroll_area).
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn scroll_to(&mut self, line: u16) -> Result<(), DisplayError> {
+        Command::VerticalScrollStartAddresss(line).send(&mut self.interface)
+    }
+
+    /// Override the `OFFSET_X` and `OFFSET_Y` of the display definition, e.g. to find the
+    /// offsets of an unknown module at runtime without a rebuild per attempt.
+    ///
+    /// The offsets apply to every later window, mirrored by the rotations like the definition
+    /// ones. Draw a 1px border, tune the offsets until it aligns with the glass edges, then
+    /// hardcode them in the [`DisplayDefinition`]. Offsets past the controller memory are
+    /// not checked.
+    pub const fn set_runtime_offset(&mut self, ox: u16, oy: u16) {
+        self.offset = (ox, oy);
+    }
+
+    /// Enter (39h) or leave (38h) idle mode.
+    ///
+    /// In idle mode the panel only shows 8 colors, using the most significant bit of each
+    /// channel, for a lower power draw. Only the output is reduced: the frame memory and any
+    /// framebuffer keep their full colors, `flush` keeps working and leaving idle mode shows
+    /// the full colors again.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn set_idle_mode(&mut self, on: bool) -> Result<(), DisplayError> {
+        let mode = if on { Logical::On } else { Logical::Off };
+        Command::IdleMode(mode).send(&mut self.interface)
+    }
+
+    /// Enter sleep mode, the lowest power state of the panel.
+    ///
+    /// The frame memory is kept, so [`wake`](Self::wake) brings the last frame back.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn sleep(&mut self, delay: &mut impl DelayNs) -> Result<(), DisplayError> {
+        Command::SleepMode(Logical::On).send(&mut self.interface)?;
+        // supply voltages and clocks need 5ms to settle
+        delay.delay_ms(5);
+
+        Ok(())
+    }
+
+    /// Leave sleep mode and turn the display back on.
+    ///
+    /// Like [`resume`](Self::resume), the display stays off while a zero brightness blanks
+    /// it, see [`ZeroBrightnessPolicy::Blank`]. This also ends a [`suspend`](Self::suspend).
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn wake(&mut self, delay: &mut impl DelayNs) -> Result<(), DisplayError> {
+        Command::SleepMode(Logical::Off).send(&mut self.interface)?;
+        // the datasheet requires 120ms before the next sleep in
+        delay.delay_ms(120);
+        self.suspended = Logical::Off;
+
+        if !self.blanked {
+            Command::DisplayState(Logical::On).send(&mut self.interface)?;
+        }
+
+        Ok(())
+    }
+
+    /// Turn the display off (28h) and enter sleep mode (10h), for the deep sleep of the MCU.
+    ///
+    /// Calling it again while suspended does nothing. The driver keeps tracking the
+    /// rotation, inversion and brightness meanwhile, so [`resume`](Self::resume) can restore
+    /// them. The panel needs 120ms in sleep mode before it can be resumed.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn suspend(&mut self, delay: &mut impl DelayNs) -> Result<(), DisplayError> {
+        if self.suspended == Logical::On {
+            return Ok(());
+        }
+
+        Command::DisplayState(Logical::Off).send(&mut self.interface)?;
+        self.sleep(delay)?;
+        self.suspended = Logical::On;
+
+        Ok(())
+    }
+
+    /// Leave the sleep mode entered by [`suspend`](Self::suspend), restore the tracked
+    /// rotation, inversion and brightness, then turn the display back on.
+    ///
+    /// The display stays off if it was blanked by a zero brightness, see
+    /// [`ZeroBrightnessPolicy::Blank`]. Calling it while not suspended does nothing.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn resume(&mut self, delay: &mut impl DelayNs) -> Result<(), DisplayError> {
+        if self.suspended == Logical::Off {
+            return Ok(());
+        }
+
+        Command::SleepMode(Logical::Off).send(&mut self.interface)?;
+        // the datasheet requires 120ms before the next sleep in
+        delay.delay_ms(120);
+        self.suspended = Logical::Off;
+
+        self.set_display_rotation(self.display_rotation)?;
+        self.set_invert_pixels(self.inverted)?;
+        if let Some((brightness_block, dimming, backlight)) = self.display_control {
+            self.set_display_control(brightness_block, dimming, backlight)?;
+        }
+        self.set_brightness(self.brightness)?;
+
+        if !self.blanked {
+            Command::DisplayState(Logical::On).send(&mut self.interface)?;
+        }
+
+        Ok(())
+    }
+
+    /// Set hardware to inverse the GDDRAM framebuffer output
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn set_invert_pixels(&mut self, value: bool) -> Result<(), DisplayError> {
+        Command::DisplayInversion(value.into()).send(&mut self.interface)?;
+        self.inverted = value;
+
+        Ok(())
+    }
+
+    /// Flip the inversion of the GDDRAM framebuffer output
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn toggle_inversion(&mut self) -> Result<(), DisplayError> {
+        self.set_invert_pixels(!self.inverted)
+    }
+
+    /// Whether the output is inverted
+    ///
+    /// The init sequences turn the inversion on, which these panels need to show the colors
+    /// as sent: a freshly initialised display reports `true`.
+    pub const fn is_inverted(&self) -> bool {
+        self.inverted
+    }
+
+    /// Only drive the rows from `start` to `end` (inclusive) and enter Partial mode.
+    ///
+    /// Rows are frame memory rows, clamped to `D::ROWS`. The rest of the panel is left blank,
+    /// which saves power when only a band of the screen is in use.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if `start` is after `end`, or if there are
+    /// communication issues with the display.
+    pub fn set_partial_area(&mut self, start: u16, end: u16) -> Result<(), DisplayError> {
+        if start > end {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        let last = D::ROWS - 1;
+        Command::PartialArea(start.min(last), end.min(last)).send(&mut self.interface)?;
+        Command::PartialMode.send(&mut self.interface)
+    }
+
+    /// Leave Partial mode and drive the whole panel again.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn exit_partial_mode(&mut self) -> Result<(), DisplayError> {
+        Command::NormalDisplayMode.send(&mut self.interface)
+    }
+
+    /// Set the draw window to the whole active area, with the offsets of the definition and
+    /// of the rotation applied.
+    ///
+    /// A `MemoryWrite` (2Ch) followed by `D::WIDTH * D::HEIGHT` pixels then fills the panel,
+    /// in panel memory order: rows of `D::WIDTH` pixels, transposed for
+    /// [`DisplayRotation::Rotate90`] and [`DisplayRotation::Rotate270`] like the
+    /// `BufferedGraphics` framebuffer.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn set_full_window(&mut self) -> Result<(), DisplayError> {
+        let (offset_x, offset_y) = self.window_offset();
+        self.set_draw_area(
+            (offset_x, offset_y),
+            (offset_x + D::WIDTH - 1, offset_y + D::HEIGHT - 1),
+        )
+    }
+
+    /// Set hardware framebuffer to configure a limited area
+    /// of the screen where any pixel should be draw.
+    ///
+    /// * (`x_start`, `y_start`) - starting point, inclusive
+    /// * (`x_end`, `y_end`) - ending point, inclusive
+    ///
+    /// The last window sent is cached: setting the same area again sends nothing, which saves
+    /// two commands per frame for fixed-region updates.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn set_draw_area(
+        &mut self,
+        start: (u16, u16),
+        end: (u16, u16),
+    ) -> Result<(), DisplayError> {
+        if self.draw_area == Some((start, end)) {
+            return Ok(());
+        }
+
+        self.draw_area = None;
+        Command::ColumnAddressSet(start.0, end.0).send(&mut self.interface)?;
+        Command::RowAddressSet(start.1, end.1).send(&mut self.interface)?;
+        self.draw_area = Some((start, end));
+
+        Ok(())
+    }
+
+    /// Set the hardware window to `area`, in controller coordinates.
+    ///
+    /// Both corners of `area` are part of the window: `Rectangle::new(Point::new(10, 20),
+    /// Size::new(30, 40))` sends columns 10 to 39 (2Ah) and rows 20 to 59 (2Bh), see
+    /// [`set_draw_area`](Self::set_draw_area).
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if `area` is empty or does not
+    /// fit in the controller memory, and may return an error if there are communication
+    /// issues with the display.
+    #[cfg(feature = "graphics")]
+    pub fn set_window(&mut self, area: Rectangle) -> Result<(), DisplayError> {
+        let memory = Rectangle::new(Point::zero(), Size::new(D::COLS.into(), D::ROWS.into()));
+        let Some(bottom_right) = area.bottom_right() else {
+            return Err(DisplayError::OutOfBoundsError);
+        };
+        if !memory.contains(area.top_left) || !memory.contains(bottom_right) {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        #[allow(clippy::cast_sign_loss)]
+        self.set_draw_area(
+            (area.top_left.x as u16, area.top_left.y as u16),
+            (bottom_right.x as u16, bottom_right.y as u16),
+        )
+    }
+
+    /// Async twin of [`set_draw_area`](Self::set_draw_area), sharing its window cache
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    #[cfg(feature = "async")]
+    pub(crate) async fn set_draw_area_async(
+        &mut self,
+        start: (u16, u16),
+        end: (u16, u16),
+    ) -> Result<(), DisplayError>
+    where
+        I: AsyncWriteOnlyDataCommand,
+    {
+        if self.draw_area == Some((start, end)) {
+            return Ok(());
+        }
+
+        self.draw_area = None;
+        Command::ColumnAddressSet(start.0, end.0)
+            .send_async(&mut self.interface)
+            .await?;
+        Command::RowAddressSet(start.1, end.1)
+            .send_async(&mut self.interface)
+            .await?;
+        self.draw_area = Some((start, end));
+
+        Ok(())
+    }
+
+    /// Set the hardware framebuffer to await incoming colors
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn set_write_mode(&mut self) -> Result<(), DisplayError> {
+        Command::MemoryWrite.send(&mut self.interface)?;
+        Ok(())
+    }
+
+    /// Send No Operation (00h), terminating the memory write in progress
+    ///
+    /// Pixels sent after it are ignored until the next memory write command, so a
+    /// `MemoryWriteContinue` (3Ch) stream can be stopped cleanly before switching window.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn nop(&mut self) -> Result<(), DisplayError> {
+        Command::Nop.send(&mut self.interface)
+    }
+
+    /// Get a reference to the underlying interface, e.g. to inspect a
+    /// [`CaptureInterface`](crate::capture::CaptureInterface) after a `flush`
+    pub const fn interface(&self) -> &I {
+        &self.interface
+    }
+
+    /// Whether the screen was initialised since creation or the last [`reset`](Self::reset)
+    pub const fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    /// Get screen rotation
+    pub const fn get_screen_rotation(&self) -> DisplayRotation {
+        self.display_rotation
+    }
+
+    /// Get pixel screen dimensions
+    pub const fn dimensions(&self) -> (u16, u16) {
+        match self.display_rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (D::WIDTH, D::HEIGHT),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (D::HEIGHT, D::WIDTH),
+        }
+    }
+
+    /// Get pixel screen bounds (x-1, y-1)
+    pub const fn bounds(&self) -> (u16, u16) {
+        match self.display_rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (D::WIDTH - 1, D::HEIGHT - 1),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (D::HEIGHT - 1, D::WIDTH - 1),
+        }
+    }
+
+    /// Check that an area of `size` with its upper left corner at `top_left` lies entirely on
+    /// the display
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if the area does not fit.
+    #[cfg(feature = "graphics")]
+    pub(crate) fn check_area_fits(&self, top_left: Point, size: Size) -> Result<(), DisplayError> {
+        let (width, height) = self.dimensions();
+        let fits = |origin: i32, extent: u32, max: u16| {
+            u32::try_from(origin).is_ok_and(|origin| origin + extent <= u32::from(max))
+        };
+
+        if fits(top_left.x, size.width, width) && fits(top_left.y, size.height, height) {
+            Ok(())
+        } else {
+            Err(DisplayError::OutOfBoundsError)
+        }
+    }
+
+    /// Check that `bytes` holds exactly the pixels of `area` in `format`, and that `area`
+    /// lies entirely on the display
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] otherwise.
+    #[cfg(feature = "graphics")]
+    pub(crate) fn check_bytes_fit(
+        &self,
+        area: &Rectangle,
+        bytes: &[u8],
+        format: PixelByteFormat,
+    ) -> Result<(), DisplayError> {
+        let pixels = area.size.width as usize * area.size.height as usize;
+        if bytes.len() != pixels * format.bytes_per_pixel() {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        self.check_area_fits(area.top_left, area.size)
+    }
+
+    /// Check that `pattern` holds exactly `size` pixels, and that `size` is not empty
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] otherwise.
+    #[cfg(feature = "graphics")]
+    pub(crate) const fn check_pattern(pattern: &[u16], size: Size) -> Result<(), DisplayError> {
+        let pixels = size.width as usize * size.height as usize;
+        if pixels == 0 || pattern.len() != pixels {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        Ok(())
+    }
+
+    /// Colors of `pattern`, a tile of `size` pixels repeated from `anchor`, over the rows of
+    /// `area`. `area` must not start before `anchor`.
+    #[cfg(feature = "graphics")]
+    pub(crate) fn tile_colors(
+        anchor: Point,
+        area: Rectangle,
+        pattern: &[u16],
+        size: Size,
+    ) -> impl Iterator<Item = u16> + '_ {
+        area.rows().flat_map(move |y| {
+            let row = (y - anchor.y).unsigned_abs() % size.height * size.width;
+
+            area.columns().map(move |x| {
+                let column = (x - anchor.x).unsigned_abs() % size.width;
+                pattern[(row + column) as usize]
+            })
+        })
+    }
+
+    /// Hardware window offset (`x`, `y`) of the active area for the current rotation and
+    /// mirroring
+    pub(crate) const fn window_offset(&self) -> (u16, u16) {
+        let (ox, oy) = self.offset;
+        let (columns_reversed, rows_reversed) = self.address_order();
+        let offset_x = if columns_reversed {
+            D::COLS.saturating_sub(D::WIDTH).saturating_sub(ox)
+        } else {
+            ox
+        };
+        let offset_y = if rows_reversed {
+            D::ROWS.saturating_sub(D::HEIGHT).saturating_sub(oy)
+        } else {
+            oy
+        };
+
+        (offset_x, offset_y)
+    }
+
+    /// Flush the buffer by chuncks
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub(crate) fn flush_buffer_chunks(
+        interface: &mut I,
+        format: Dbi,
+        buffer: &[u16],
+        disp_width: usize,
+        upper_left: (u16, u16),
+        lower_right: (u16, u16),
+    ) -> Result<(), DisplayError> {
+        Command::MemoryWrite.send(interface)?;
+
+        // Number of rows to process (Y range)
+        let num_pages = (lower_right.1 - upper_left.1 + 1) as usize;
+
+        // Starting row (Y coordinate)
+        let starting_page = upper_left.1 as usize;
+
+        // X coordinates (columns) for the rectangle
+        let page_lower = upper_left.0 as usize;
         let page_upper = ((lower_right.0 + 1) as usize).min(disp_width); // +1 to include the last column
 
         // Process the buffer in rows (chunks of disp_width)
-        buffer
+        let mut rows = buffer
             .chunks(disp_width)
             .skip(starting_page)
             .take(num_pages)
-            .map(|s| &s[page_lower..page_upper])
-            .try_for_each(|c| interface.send_data(DataFormat::U16BEIter(&mut c.iter().copied())))
+            .map(|s| &s[page_lower..page_upper]);
+
+        if format == Dbi::Pixel16bits {
+            return rows.try_for_each(|c| Self::send_pixels(interface, format, c));
+        }
+
+        // packed pixels may straddle two rows, pack them as one stream
+        let mut pixels = rows.flatten().copied();
+        let mut scratch = [0u8; PIXEL_SCRATCH_LEN * 3];
+        loop {
+            match pack_pixels(format, &mut pixels, &mut scratch) {
+                0 => return Ok(()),
+                len => interface.send_data(DataFormat::U8(&scratch[..len]))?,
+            }
+        }
+    }
+
+    /// Send pixels as big-endian words.
+    ///
+    /// Pixels are staged through a small stack scratch and sent as `DataFormat::U16BE` slices,
+    /// which interfaces swap in bulk (and may hand to DMA) rather than pulling word by word
+    /// through the iterator. Interfaces without slice support fall back to `U16BEIter`.
+    ///
+    /// Other `format`s than 16-bit are packed to bytes, see [`pack_pixels`].
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub(crate) fn send_pixels(
+        interface: &mut I,
+        format: Dbi,
+        pixels: &[u16],
+    ) -> Result<(), DisplayError> {
+        if format != Dbi::Pixel16bits {
+            let mut pixels = pixels.iter().copied();
+            let mut scratch = [0u8; PIXEL_SCRATCH_LEN * 3];
+            loop {
+                match pack_pixels(format, &mut pixels, &mut scratch) {
+                    0 => return Ok(()),
+                    len => interface.send_data(DataFormat::U8(&scratch[..len]))?,
+                }
+            }
+        }
+
+        let mut scratch = [0u16; PIXEL_SCRATCH_LEN];
+
+        for (sent, chunk) in pixels.chunks(PIXEL_SCRATCH_LEN).enumerate() {
+            let scratch = &mut scratch[..chunk.len()];
+            scratch.copy_from_slice(chunk);
+
+            match interface.send_data(DataFormat::U16BE(scratch)) {
+                Err(DisplayError::DataFormatNotImplemented) => {
+                    let rest = &pixels[sent * PIXEL_SCRATCH_LEN..];
+                    return interface.send_data(DataFormat::U16BEIter(&mut rest.iter().copied()));
+                }
+                result => result?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Async twin of [`send_pixels`](Self::send_pixels)
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    #[cfg(feature = "async")]
+    pub(crate) async fn send_pixels_async(
+        interface: &mut I,
+        format: Dbi,
+        pixels: &[u16],
+    ) -> Result<(), DisplayError>
+    where
+        I: AsyncWriteOnlyDataCommand,
+    {
+        if format != Dbi::Pixel16bits {
+            let mut pixels = pixels.iter().copied();
+            let mut scratch = [0u8; PIXEL_SCRATCH_LEN * 3];
+            loop {
+                match pack_pixels(format, &mut pixels, &mut scratch) {
+                    0 => return Ok(()),
+                    len => {
+                        AsyncWriteOnlyDataCommand::send_data(
+                            interface,
+                            DataFormat::U8(&scratch[..len]),
+                        )
+                        .await?;
+                    }
+                }
+            }
+        }
+
+        let mut scratch = [0u16; PIXEL_SCRATCH_LEN];
+
+        for (sent, chunk) in pixels.chunks(PIXEL_SCRATCH_LEN).enumerate() {
+            let scratch = &mut scratch[..chunk.len()];
+            scratch.copy_from_slice(chunk);
+
+            match AsyncWriteOnlyDataCommand::send_data(interface, DataFormat::U16BE(scratch)).await
+            {
+                Err(DisplayError::DataFormatNotImplemented) => {
+                    let rest = &pixels[sent * PIXEL_SCRATCH_LEN..];
+                    let mut rest = rest.iter().copied();
+                    return AsyncWriteOnlyDataCommand::send_data(
+                        interface,
+                        DataFormat::U16BEIter(&mut rest),
+                    )
+                    .await;
+                }
+                result => result?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Pack Rgb565 `pixels` to the wire bytes of `format` into `bytes`, until it is nearly full.
+///
+/// * 12-bit: two pixels in three bytes (`R1G1`, `B1R2`, `G2B2`), a trailing odd pixel in
+///   two bytes with the unused nibble cleared
+/// * 18-bit: three bytes per pixel, each channel in the six upper bits
+/// * 16-bit: big-endian words
+///
+/// Returns the number of bytes written, `0` once `pixels` is exhausted. `bytes` must hold at
+/// least three bytes.
+pub(crate) fn pack_pixels(
+    format: Dbi,
+    pixels: &mut impl Iterator<Item = u16>,
+    bytes: &mut [u8],
+) -> usize {
+    const fn channels(pixel: u16) -> (u8, u8, u8) {
+        (
+            (pixel >> 11) as u8,
+            ((pixel >> 5) & 0x3F) as u8,
+            (pixel & 0x1F) as u8,
+        )
+    }
+
+    let mut len = 0;
+    while len + 3 <= bytes.len() {
+        let Some(pixel) = pixels.next() else {
+            break;
+        };
+        let (r, g, b) = channels(pixel);
+
+        match format {
+            Dbi::Pixel12bits => {
+                let (r, g, b) = (r >> 1, g >> 2, b >> 1);
+                bytes[len] = (r << 4) | g;
+
+                if let Some(pixel) = pixels.next() {
+                    let (r2, g2, b2) = channels(pixel);
+                    bytes[len + 1] = (b << 4) | (r2 >> 1);
+                    bytes[len + 2] = ((g2 >> 2) << 4) | (b2 >> 1);
+                    len += 3;
+                } else {
+                    bytes[len + 1] = b << 4;
+                    len += 2;
+                }
+            }
+            Dbi::Pixel18bits => {
+                bytes[len..len + 3].copy_from_slice(&[r << 3, g << 2, b << 3]);
+                len += 3;
+            }
+            Dbi::Pixel16bits => {
+                bytes[len..len + 2].copy_from_slice(&pixel.to_be_bytes());
+                len += 2;
+            }
+        }
+    }
+
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        capture::{commands, params, CaptureInterface},
+        display::DisplayResolution240x240,
+        mode::BasicMode,
+    };
+
+    type Capture = CaptureInterface<{ 240 * 240 * 2 + 256 }>;
+
+    struct NoDelay;
+
+    impl DelayNs for NoDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    struct Pin;
+
+    impl embedded_hal::digital::ErrorType for Pin {
+        type Error = core::convert::Infallible;
+    }
+
+    impl OutputPin for Pin {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    fn display() -> Gc9a01<Capture, DisplayResolution240x240, BasicMode> {
+        Gc9a01::new(
+            Capture::new(),
+            DisplayResolution240x240,
+            DisplayRotation::Rotate0,
+        )
+    }
+
+    #[test]
+    fn clear_fit_sends_every_pixel() -> Result<(), DisplayError> {
+        let mut display = display();
+        display.clear_fit()?;
+
+        let captured = display.interface().captured();
+        assert_eq!(params(captured, 0x2A), [[0, 0, 0, 239]]);
+        assert_eq!(params(captured, 0x2B), [[0, 0, 0, 239]]);
+        assert_eq!(params(captured, 0x2C)[0].len(), 2 * 240 * 240);
+
+        Ok(())
+    }
+
+    #[test]
+    fn same_window_is_not_sent_again() -> Result<(), DisplayError> {
+        let mut display = Gc9a01::new(
+            CaptureInterface::<256>::new(),
+            DisplayResolution240x240,
+            DisplayRotation::Rotate0,
+        )
+        .into_buffered_graphics();
+
+        display.set_pixel(120, 120, 0x1234);
+        display.flush()?;
+        let first = display.interface().captured().len();
+
+        display.set_pixel(120, 120, 0x5678);
+        display.flush()?;
+
+        let captured = &display.interface().captured()[first..];
+        assert_eq!(commands(captured), [(0x2C, vec![0x56, 0x78])]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reset_restores_the_power_on_state() -> Result<(), DisplayError> {
+        let mut display = display();
+        display.init_with_addr_mode(&mut NoDelay)?;
+        display.set_brightness(Brightness::BRIGHT)?;
+        display.set_draw_area((0, 0), (9, 9))?;
+        display.suspend(&mut NoDelay)?;
+
+        display
+            .reset(&mut Pin, &mut NoDelay)
+            .map_err(|_reset| DisplayError::RSError)?;
+
+        let power_on = self::display();
+        assert_eq!(display.draw_area, power_on.draw_area);
+        assert_eq!(display.brightness, power_on.brightness);
+        assert_eq!(display.blanked, power_on.blanked);
+        assert_eq!(display.inverted, power_on.inverted);
+        assert_eq!(display.suspended, power_on.suspended);
+        assert_eq!(display.display_control, power_on.display_control);
+        assert_eq!(display.initialized, power_on.initialized);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn set_window_sends_inclusive_corners() -> Result<(), DisplayError> {
+        let mut display = display();
+        display.set_window(Rectangle::new(Point::new(10, 20), Size::new(30, 40)))?;
+
+        let captured = display.interface().captured();
+        assert_eq!(params(captured, 0x2A), [[0, 10, 0, 39]]);
+        assert_eq!(params(captured, 0x2B), [[0, 20, 0, 59]]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn init_enables_brightness_control_before_brightness() -> Result<(), DisplayError> {
+        let mut display = display();
+        display.init_with_addr_mode(&mut NoDelay)?;
+
+        let sent: Vec<u8> = commands(display.interface().captured())
+            .into_iter()
+            .map(|(cmd, _params)| cmd)
+            .collect();
+        let ctrl = sent.iter().position(|&cmd| cmd == 0x53);
+        let brightness = sent.iter().position(|&cmd| cmd == 0x51);
+        assert!(ctrl.is_some() && brightness.is_some());
+        assert!(ctrl < brightness);
+
+        Ok(())
+    }
+
+    #[test]
+    fn madctl_differs_per_rotation() -> Result<(), DisplayError> {
+        let mut display = display();
+        for rotation in [
+            DisplayRotation::Rotate0,
+            DisplayRotation::Rotate90,
+            DisplayRotation::Rotate180,
+            DisplayRotation::Rotate270,
+        ] {
+            display.set_display_rotation(rotation)?;
+        }
+
+        let madctl = params(display.interface().captured(), 0x36);
+        assert_eq!(madctl, [[0x18], [0x98], [0xD8], [0x58]]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn wake_keeps_a_blanked_display_off() -> Result<(), DisplayError> {
+        let mut display = display();
+        display.set_zero_brightness_policy(ZeroBrightnessPolicy::Blank);
+        display.set_brightness(Brightness::DIMMEST)?;
+        display.suspend(&mut NoDelay)?;
+        let suspended = display.interface().captured().len();
+
+        display.wake(&mut NoDelay)?;
+
+        let captured = &display.interface().captured()[suspended..];
+        assert!(params(captured, 0x29).is_empty());
+        assert_eq!(display.suspended, Logical::Off);
+
+        Ok(())
+    }
+
+    #[test]
+    fn init_from_table_keeps_the_given_state() -> Result<(), DisplayError> {
+        let mut display = display();
+        display.init_from_table(
+            &[(0x11, &[], 120), (0x3A, &[0x66], 0)],
+            Dbi::Pixel18bits,
+            false,
+            &mut NoDelay,
+        )?;
+
+        assert_eq!(display.pixel_format(), Dbi::Pixel18bits);
+        assert!(!display.is_inverted());
+
+        Ok(())
+    }
+
+    /// Capture interface taking only the first `slices` `U16BE` writes, like a bus without
+    /// slice support after a partial transfer
+    struct IterOnly {
+        capture: CaptureInterface<1024>,
+        slices: usize,
+    }
+
+    impl WriteOnlyDataCommand for IterOnly {
+        fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+            WriteOnlyDataCommand::send_commands(&mut self.capture, cmd)
+        }
+
+        fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+            if matches!(buf, DataFormat::U16BE(_)) {
+                if self.slices == 0 {
+                    return Err(DisplayError::DataFormatNotImplemented);
+                }
+                self.slices -= 1;
+            }
+            WriteOnlyDataCommand::send_data(&mut self.capture, buf)
+        }
+    }
+
+    #[test]
+    fn send_pixels_falls_back_to_the_iterator() -> Result<(), DisplayError> {
+        type Driver<I> = Gc9a01<I, DisplayResolution240x240, BasicMode>;
+
+        let pixels: Vec<u16> = (0..300)
+            .map(|pixel: u16| pixel.wrapping_mul(0x0101))
+            .collect();
+        let mut sliced = CaptureInterface::<1024>::new();
+        Driver::send_pixels(&mut sliced, Dbi::Pixel16bits, &pixels)?;
+
+        for slices in [0, 1, 2] {
+            let mut fallback = IterOnly {
+                capture: CaptureInterface::new(),
+                slices,
+            };
+            Driver::send_pixels(&mut fallback, Dbi::Pixel16bits, &pixels)?;
+
+            assert_eq!(fallback.capture.captured(), sliced.captured());
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,158 @@
+//! Display gamma
+
+use crate::command::{Gamma1, Gamma2, Gamma3, Gamma4};
+
+/// Stock negative polarity curve, first half (F0h)
+const STOCK_GAMMA1: Gamma1 = Gamma1 {
+    dig2j0_n: 0b1,
+    vr1_n: 0b00_0101,
+    dig2j1_n: 0b0,
+    vr2_n: 0b00_1001,
+    vr4_n: 0b1000,
+    vr6_n: 0b1000,
+    vr0_n: 0b10,
+    vr13_n: 0b0110,
+    vr20_n: 0b10_1010,
+};
+
+/// Stock negative polarity curve, second half (F1h)
+const STOCK_GAMMA2: Gamma2 = Gamma2 {
+    vr43_n: 0b100_0011,
+    vr27_n: 0b11,
+    vr57_n: 0b1_0000,
+    vr36_n: 0b11,
+    vr59_n: 0b1_0010,
+    vr61_n: 0b11_0110,
+    vr62_n: 0b11_0111,
+    vr50_n: 0b110,
+    vr63_n: 0b1111,
+};
+
+/// High-level gamma curve builder
+///
+/// The GC9A01 gamma curve is set by voltage taps `VRn` at fixed grey levels `n` (0 is black,
+/// 63 is white), packed into the [`Gamma1`]..[`Gamma4`] registers. Each knob adds a signed
+/// offset to every tap of its band, clamped to the register field width:
+///
+/// | Knob                                     | Taps                                      |
+/// |------------------------------------------|-------------------------------------------|
+/// | [`shadows`](GammaBuilder::shadows)       | `VR0`, `VR1`, `VR2`, `VR4`, `VR6`, `VR13` |
+/// | [`midtones`](GammaBuilder::midtones)     | `VR20`, `VR27`, `VR36`, `VR43`, `VR50`    |
+/// | [`highlights`](GammaBuilder::highlights) | `VR57`, `VR59`, `VR61`, `VR62`, `VR63`    |
+///
+/// The same curve is written to the negative (F0h/F1h) and positive (F2h/F3h) polarities.
+/// The controller drives the red, green and blue subpixels from a single curve, so there is
+/// no per-channel knob. With every knob at `0` the builder yields the stock curve sent by
+/// [`DisplayResolution240x240`](crate::display::DisplayResolution240x240).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct GammaBuilder {
+    shadows: i8,
+    midtones: i8,
+    highlights: i8,
+}
+
+impl GammaBuilder {
+    /// Builder with every knob neutral, yielding the stock curve
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            shadows: 0,
+            midtones: 0,
+            highlights: 0,
+        }
+    }
+
+    /// Offset of the dark taps, positive values lift the shadows
+    #[must_use]
+    pub const fn shadows(mut self, offset: i8) -> Self {
+        self.shadows = offset;
+        self
+    }
+
+    /// Offset of the middle taps
+    #[must_use]
+    pub const fn midtones(mut self, offset: i8) -> Self {
+        self.midtones = offset;
+        self
+    }
+
+    /// Offset of the bright taps
+    #[must_use]
+    pub const fn highlights(mut self, offset: i8) -> Self {
+        self.highlights = offset;
+        self
+    }
+
+    /// Compute the four gamma registers
+    #[must_use]
+    pub const fn build(&self) -> (Gamma1, Gamma2, Gamma3, Gamma4) {
+        let (g1, g2) = (STOCK_GAMMA1, STOCK_GAMMA2);
+        let (s, m, h) = (self.shadows, self.midtones, self.highlights);
+
+        let gamma1 = Gamma1 {
+            dig2j0_n: g1.dig2j0_n,
+            vr1_n: tap(g1.vr1_n, s, 6),
+            dig2j1_n: g1.dig2j1_n,
+            vr2_n: tap(g1.vr2_n, s, 6),
+            vr4_n: tap(g1.vr4_n, s, 5),
+            vr6_n: tap(g1.vr6_n, s, 5),
+            vr0_n: tap(g1.vr0_n, s, 4),
+            vr13_n: tap(g1.vr13_n, s, 4),
+            vr20_n: tap(g1.vr20_n, m, 7),
+        };
+
+        let gamma2 = Gamma2 {
+            vr43_n: tap(g2.vr43_n, m, 7),
+            vr27_n: tap(g2.vr27_n, m, 3),
+            vr57_n: tap(g2.vr57_n, h, 5),
+            vr36_n: tap(g2.vr36_n, m, 3),
+            vr59_n: tap(g2.vr59_n, h, 5),
+            vr61_n: tap(g2.vr61_n, h, 6),
+            vr62_n: tap(g2.vr62_n, h, 6),
+            vr50_n: tap(g2.vr50_n, m, 4),
+            vr63_n: tap(g2.vr63_n, h, 4),
+        };
+
+        let gamma3 = Gamma3 {
+            dig2j0_p: gamma1.dig2j0_n,
+            vr1_p: gamma1.vr1_n,
+            dig2j1_p: gamma1.dig2j1_n,
+            vr2_p: gamma1.vr2_n,
+            vr4_p: gamma1.vr4_n,
+            vr6_p: gamma1.vr6_n,
+            vr0_p: gamma1.vr0_n,
+            vr13_p: gamma1.vr13_n,
+            vr20_p: gamma1.vr20_n,
+        };
+
+        let gamma4 = Gamma4 {
+            vr43_p: gamma2.vr43_n,
+            vr27_p: gamma2.vr27_n,
+            vr57_p: gamma2.vr57_n,
+            vr36_p: gamma2.vr36_n,
+            vr59_p: gamma2.vr59_n,
+            vr61_p: gamma2.vr61_n,
+            vr62_p: gamma2.vr62_n,
+            vr50_p: gamma2.vr50_n,
+            vr63_p: gamma2.vr63_n,
+        };
+
+        (gamma1, gamma2, gamma3, gamma4)
+    }
+}
+
+/// Offset a tap value, clamped to a register field of `bits` bits
+const fn tap(stock: u8, offset: i8, bits: u32) -> u8 {
+    let max = (1u8 << bits) - 1;
+
+    if offset < 0 {
+        stock.saturating_sub(offset.unsigned_abs())
+    } else {
+        let value = stock.saturating_add(offset.unsigned_abs());
+        if value > max {
+            max
+        } else {
+            value
+        }
+    }
+}
@@ -58,7 +58,6 @@
     clippy::separated_literal_suffix,
     clippy::str_to_string,
     clippy::string_add,
-    clippy::string_to_string,
     clippy::unnecessary_self_imports,
     clippy::unneeded_field_pattern,
     clippy::verbose_file_reads
@@ -72,21 +71,31 @@
     clippy::indexing_slicing
 )]
 
+// export capture interface
+#[cfg(any(test, feature = "capture"))]
+pub mod capture;
+// export clock abstraction
+pub mod clock;
 // export commands
 pub mod command;
 // export screen configuration
 pub mod display;
 // export modes
 pub mod mode;
+// export pixel byte formats
+pub mod pixel;
 // prelude
 pub mod prelude;
 // export screen rotation mode
 pub mod rotation;
+// export coordinate transform
+pub mod transform;
 
 mod brightness;
 mod driver;
+mod gamma;
 mod spi;
 
 // export the driver and interface
 pub use driver::Gc9a01;
-pub use spi::SPIDisplayInterface;
+pub use spi::{NoCs, ReadableInterface, SPI3WireInterface, SPIBusInterface, SPIDisplayInterface};
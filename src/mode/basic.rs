@@ -3,7 +3,13 @@
 use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
 use embedded_hal::delay::DelayNs;
 
-use crate::{display::DisplayDefinition, rotation::DisplayRotation, Gc9a01};
+use crate::{
+    brightness::{Brightness, ZeroBrightnessPolicy},
+    command::{Dbi, Logical},
+    display::DisplayDefinition,
+    rotation::DisplayRotation,
+    Gc9a01,
+};
 
 use super::DisplayConfiguration;
 
@@ -24,20 +30,45 @@ where
     I: WriteOnlyDataCommand,
     D: DisplayDefinition,
 {
+    /// Create a basic [`Gc9a01`] interface.
+    ///
+    /// Use the `into_buffed_graphics` methods to enable more functionality.
+    pub fn new(interface: I, screen: D, screen_rotation: DisplayRotation) -> Self {
+        Self {
+            interface,
+            display: screen,
+            mode: BasicMode::new(),
+            display_rotation: screen_rotation,
+            mirror: (false, false),
+            bgr: D::DEFAULT_BGR.into(),
+            pixel_format: Dbi::Pixel16bits,
+            offset: (D::OFFSET_X, D::OFFSET_Y),
+            zero_brightness_policy: ZeroBrightnessPolicy::default(),
+            brightness: Brightness::DIMMEST,
+            blanked: false,
+            inverted: false,
+            suspended: Logical::Off,
+            display_control: None,
+            draw_area: None,
+            initialized: false,
+        }
+    }
+
     /// Clear the display
     ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
     pub fn clear(&mut self) -> Result<(), DisplayError> {
-        self.set_draw_area((0, 0), self.bounds())?;
-        self.set_write_mode()?;
         self.clear_fit()
     }
 
     /// Set the pixels directly to the hardware by setting the window from `start` to `end` based
     /// on the `Iterator<Item = u16>` provided.
     ///
+    /// Coordinates are in display space: the active area offset and the rotation are applied
+    /// like the [`BufferedGraphics`](crate::mode::BufferedGraphics) flush does.
+    ///
     /// This function does not protect the user input.
     ///
     /// # Errors
@@ -49,21 +80,264 @@ where
         end: (u16, u16),
         colors: &mut dyn Iterator<Item = u16>,
     ) -> Result<(), DisplayError> {
-        self.set_draw_area(start, end)?;
+        self.stream_window(start, end, colors)
+    }
+
+    /// Stream a full frame to the display, without any framebuffer or dirty tracking.
+    ///
+    /// The whole active area is selected once and `frame` is sent as slices of pixels,
+    /// letting the interface use its largest writes. This is the fastest path for video-like
+    /// sources (camera, decoded animations). Outside of 16-bit mode, see
+    /// [`set_pixel_format`](Self::set_pixel_format), the pixels are packed on the fly.
+    ///
+    /// `frame` holds native Rgb565 values (as produced by `RawU16::into_inner`), sent MSB
+    /// first. It is laid out in panel memory order: `D::HEIGHT` rows of `D::WIDTH` pixels.
+    /// The flips of [`DisplayRotation::Rotate180`] are applied by the hardware, while
+    /// [`DisplayRotation::Rotate90`] and [`DisplayRotation::Rotate270`] expect the frame
+    /// already transposed, like the [`BufferedGraphics`](crate::mode::BufferedGraphics)
+    /// framebuffer.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    /// This method returns [`DisplayError::OutOfBoundsError`] if `frame` is not exactly
+    /// `D::WIDTH * D::HEIGHT` pixels.
+    pub fn push_frame(&mut self, frame: &[u16]) -> Result<(), DisplayError> {
+        if frame.len() != D::PIXELS {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        self.set_full_window()?;
         self.set_write_mode()?;
-        self.interface.send_data(DataFormat::U16BEIter(colors))
+        Self::send_pixels(&mut self.interface, self.pixel_format, frame)
+    }
+
+    /// Plot scattered pixels directly through the hardware.
+    ///
+    /// Consecutive points that extend each other on the same row are merged into a single
+    /// windowed write, so a scanline-ordered stream (e.g. sorted plot data) costs one window
+    /// per span instead of one per pixel. Points are not reordered, since this works without
+    /// allocation. Points outside of the display are ignored.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    #[cfg(feature = "graphics")]
+    pub fn draw_points<P>(&mut self, points: P) -> Result<(), DisplayError>
+    where
+        P: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        const SPAN_SIZE: usize = 64;
+
+        let (width, height) = self.dimensions();
+        let mut span = [0u16; SPAN_SIZE];
+        let mut len = 0;
+        let mut origin = (0u16, 0u16);
+
+        for Pixel(pos, color) in points {
+            let (Ok(x), Ok(y)) = (u16::try_from(pos.x), u16::try_from(pos.y)) else {
+                continue;
+            };
+            if x >= width || y >= height {
+                continue;
+            }
+
+            let extends = len > 0 && len < SPAN_SIZE && y == origin.1 && x == origin.0 + len as u16;
+            if !extends && len > 0 {
+                self.set_pixels(
+                    origin,
+                    (origin.0 + len as u16 - 1, origin.1),
+                    &mut span[..len].iter().copied(),
+                )?;
+                len = 0;
+            }
+
+            if len == 0 {
+                origin = (x, y);
+            }
+            span[len] = RawU16::from(color).into_inner();
+            len += 1;
+        }
+
+        if len > 0 {
+            self.set_pixels(
+                origin,
+                (origin.0 + len as u16 - 1, origin.1),
+                &mut span[..len].iter().copied(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Draw an embedded-graphics [`ImageRaw`] with its upper left corner at `top_left`.
+    ///
+    /// Pixels are decoded with the byte order declared by the image type, so assets stored
+    /// big-endian or little-endian both work without swapping them first.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if the image does not entirely
+    /// fit on the display, or if there are communication issues with the display.
+    #[cfg(feature = "image")]
+    pub fn draw_image_raw<'a, BO>(
+        &mut self,
+        top_left: Point,
+        image: &ImageRaw<'a, Rgb565, BO>,
+    ) -> Result<(), DisplayError>
+    where
+        BO: ByteOrder,
+        ImageRaw<'a, Rgb565, BO>: ImageDrawable<Color = Rgb565>,
+    {
+        self.check_area_fits(top_left, image.size())?;
+        image.draw(&mut self.translated(top_left))
+    }
+
+    /// Draw packed pixel `bytes` into `area`, row by row, directly through the hardware.
+    ///
+    /// This takes decoder output as is: Rgb565 in either byte order, or Rgb888 packed to
+    /// Rgb565 on the fly.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if `area` does not entirely fit
+    /// on the display or `bytes` is not exactly its pixels in `format`, or if there are
+    /// communication issues with the display.
+    #[cfg(feature = "graphics")]
+    pub fn draw_bytes(
+        &mut self,
+        area: Rectangle,
+        bytes: &[u8],
+        format: PixelByteFormat,
+    ) -> Result<(), DisplayError> {
+        self.check_bytes_fit(&area, bytes, format)?;
+
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+
+        #[allow(clippy::cast_sign_loss)]
+        self.set_pixels(
+            (area.top_left.x as u16, area.top_left.y as u16),
+            (bottom_right.x as u16, bottom_right.y as u16),
+            &mut format.pixels(bytes),
+        )
+    }
+
+    /// Draw `pixels` into `area`, row by row, directly through the hardware.
+    ///
+    /// `area` is in display coordinates: the active area offset and the rotation are applied
+    /// like the [`BufferedGraphics`](crate::mode::BufferedGraphics) flush does, so the image
+    /// lands where the same pixels would after a buffered draw. The window is set once and
+    /// `pixels` streamed into it, except in [`DisplayRotation::Rotate90`] and
+    /// [`DisplayRotation::Rotate270`] where each row is its own one column window. Missing
+    /// pixels leave the rest of `area` untouched, extra ones are ignored.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if `area` does not entirely fit
+    /// on the display, or if there are communication issues with the display.
+    #[cfg(feature = "graphics")]
+    pub fn draw_image<P>(&mut self, area: Rectangle, pixels: P) -> Result<(), DisplayError>
+    where
+        P: IntoIterator<Item = u16>,
+    {
+        self.check_area_fits(area.top_left, area.size)?;
+
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+
+        #[allow(clippy::cast_sign_loss)]
+        self.stream_window(
+            (area.top_left.x as u16, area.top_left.y as u16),
+            (bottom_right.x as u16, bottom_right.y as u16),
+            &mut pixels.into_iter(),
+        )
+    }
+
+    /// Fill `area` by repeating `pattern`, a tile of `pattern_size` pixels in row-major
+    /// order, directly through the hardware.
+    ///
+    /// The tiles start at the upper left corner of `area` and are streamed without building
+    /// the whole area in memory. Tiles that don't evenly divide `area` are cut at its right
+    /// and bottom edges, and the parts of `area` outside of the display are ignored.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if `pattern` is empty or not
+    /// exactly `pattern_size` pixels, or may return an error if there are communication issues
+    /// with the display.
+    #[cfg(feature = "graphics")]
+    pub fn fill_tiled(
+        &mut self,
+        area: Rectangle,
+        pattern: &[u16],
+        pattern_size: Size,
+    ) -> Result<(), DisplayError> {
+        Self::check_pattern(pattern, pattern_size)?;
+
+        let visible = area.intersection(&self.bounding_box());
+        let Some(bottom_right) = visible.bottom_right() else {
+            return Ok(());
+        };
+
+        #[allow(clippy::cast_sign_loss)]
+        self.set_pixels(
+            (visible.top_left.x as u16, visible.top_left.y as u16),
+            (bottom_right.x as u16, bottom_right.y as u16),
+            &mut Self::tile_colors(area.top_left, visible, pattern, pattern_size),
+        )
+    }
+
+    /// Stream `colors` into the display window from `start` to `end` (inclusive), row by row.
+    ///
+    /// Display coordinates are mapped to the panel memory like the buffered flush: shifted by
+    /// the active area offset, and transposed for the rotations the hardware doesn't swap.
+    fn stream_window(
+        &mut self,
+        start: (u16, u16),
+        end: (u16, u16),
+        colors: &mut dyn Iterator<Item = u16>,
+    ) -> Result<(), DisplayError> {
+        let (offset_x, offset_y) = self.window_offset();
+
+        match self.display_rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
+                self.set_draw_area(
+                    (start.0 + offset_x, start.1 + offset_y),
+                    (end.0 + offset_x, end.1 + offset_y),
+                )?;
+                self.set_write_mode()?;
+                self.interface.send_data(DataFormat::U16BEIter(colors))
+            }
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
+                let width = usize::from(end.0 - start.0) + 1;
+                for y in start.1..=end.1 {
+                    self.set_draw_area(
+                        (y + offset_x, start.0 + offset_y),
+                        (y + offset_x, end.0 + offset_y),
+                    )?;
+                    self.set_write_mode()?;
+                    self.interface
+                        .send_data(DataFormat::U16BEIter(&mut colors.take(width)))?;
+                }
+                Ok(())
+            }
+        }
     }
 
     /// Set a pixel color at `x` and `y` coordinates directly through the hardware.
     ///
+    /// Like [`set_pixels`](Self::set_pixels), the coordinates are in display space.
+    ///
     /// This function does not protect the user input.
     ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
     pub fn set_pixel(&mut self, x: u16, y: u16, value: u16) -> Result<(), DisplayError> {
-        self.set_draw_area((x, y), (x, y))?;
-        self.interface.send_data(DataFormat::U16BE(&mut [value]))
+        self.stream_window((x, y), (x, y), &mut core::iter::once(value))
     }
 }
 
@@ -107,6 +381,16 @@ use embedded_graphics_core::{
     Pixel,
 };
 
+#[cfg(feature = "graphics")]
+use crate::pixel::PixelByteFormat;
+
+#[cfg(feature = "image")]
+use embedded_graphics::{
+    draw_target::DrawTargetExt,
+    image::{ImageDrawable, ImageRaw},
+    pixelcolor::raw::ByteOrder,
+};
+
 #[cfg(feature = "graphics")]
 impl<I, D> OriginDimensions for Gc9a01<I, D, BasicMode>
 where
@@ -176,7 +460,7 @@ where
     }
 
     fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
-        let (width, height) = self.bounds();
+        let (width, height) = self.dimensions();
         let area = area.intersection(&Rectangle {
             top_left: Point::zero(),
             size: Size::new(width.into(), height.into()),
@@ -214,3 +498,40 @@ where
         )
     }
 }
+
+#[cfg(all(test, feature = "graphics"))]
+mod tests {
+    use embedded_graphics_core::{
+        draw_target::DrawTarget,
+        geometry::{Point, Size},
+        pixelcolor::{Rgb565, RgbColor},
+        primitives::Rectangle,
+    };
+
+    use super::*;
+    use crate::{
+        capture::{params, CaptureInterface},
+        display::DisplayResolution240x240,
+    };
+
+    #[test]
+    fn fill_solid_covers_the_whole_display() -> Result<(), DisplayError> {
+        let mut display = Gc9a01::new(
+            CaptureInterface::<{ 240 * 240 * 2 + 64 }>::new(),
+            DisplayResolution240x240,
+            DisplayRotation::Rotate0,
+        );
+
+        display.fill_solid(
+            &Rectangle::new(Point::zero(), Size::new(240, 240)),
+            Rgb565::WHITE,
+        )?;
+
+        let captured = display.interface().captured();
+        assert_eq!(params(captured, 0x2A), [[0, 0, 0, 239]]);
+        assert_eq!(params(captured, 0x2B), [[0, 0, 0, 239]]);
+        assert_eq!(params(captured, 0x2C)[0].len(), 240 * 240 * 2);
+
+        Ok(())
+    }
+}
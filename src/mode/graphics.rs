@@ -1,16 +1,52 @@
 //! Buffered Graphic Implementation
 
+use core::ops::Range;
+
 use display_interface::{DisplayError, WriteOnlyDataCommand};
 
+#[cfg(feature = "async")]
+use display_interface::AsyncWriteOnlyDataCommand;
+
+#[cfg(feature = "async")]
+use crate::command::Command;
+#[cfg(feature = "async")]
+use embedded_hal_async::digital::Wait;
+
+#[cfg(feature = "queue")]
+use crate::driver::pack_pixels;
+
 use crate::{
+    clock::Clock,
+    command::Dbi,
     display::{DisplayDefinition, NewZeroed},
+    driver::PIXEL_SCRATCH_LEN,
     rotation::DisplayRotation,
+    transform::Affine2,
     Gc9a01,
 };
 
+#[cfg(feature = "graphics")]
+use crate::transform::sin_degrees;
+
 use super::DisplayConfiguration;
 
-use embedded_hal::delay::DelayNs;
+use embedded_hal::{delay::DelayNs, digital::InputPin};
+
+/// Bus rate assumed by `flush_before` until `set_bus_rate` is called
+const DEFAULT_BUS_RATE_HZ: u32 = 10_000_000;
+
+/// Interval between two reads of the TE pin in `flush_synced`
+const TE_POLL_US: u32 = 10;
+
+/// Longest wait for each TE level in `flush_synced`, several frames even at slow frame rates
+const TE_TIMEOUT_US: u32 = 100_000;
+
+/// Upper bound of the window commands (2Ah, 2Bh, 2Ch) sent per flushed row
+const WINDOW_OVERHEAD_BITS: u64 = 11 * 8;
+
+/// Unchanged pixels worth sending by `flush_diff` to avoid opening another window
+#[cfg(feature = "double-buffer")]
+const DIFF_MERGE_GAP: u16 = (WINDOW_OVERHEAD_BITS / 16) as u16;
 
 /// Buffered Graphic Implementation
 ///
@@ -18,6 +54,10 @@ use embedded_hal::delay::DelayNs;
 /// This buffer is drawn to by [`set_pixel`](Gc9a01::set_pixel) commands or
 /// [`embedded-graphics`](https://docs.rs/embedded-graphics) commands.
 /// The display can then be updated using the [`flush`](Gc9a01::flush) method.
+///
+/// The buffer stores native Rgb565 values, as produced by `RawU16::from(color).into_inner()`,
+/// and every flush path swaps them to the wire order of the panel, most significant byte
+/// first: `Rgb565::RED` is stored as `0xF800` and sent as `F8h 00h`.
 #[derive(Debug, Clone)]
 pub struct BufferedGraphics<D>
 where
@@ -28,6 +68,69 @@ where
     max_x: u16,
     min_y: u16,
     max_y: u16,
+    all_dirty: bool,
+    drawn: Option<((u16, u16), (u16, u16))>,
+    transform: Option<Affine2>,
+    clip: Option<((u16, u16), (u16, u16))>,
+    bus_rate_hz: u32,
+    present: Option<(FlushWindow, u16)>,
+    #[cfg(feature = "nb")]
+    pending_flush: Option<(FlushWindow, u16)>,
+    #[cfg(feature = "queue")]
+    queued_flush: Option<(FlushWindow, u16)>,
+    #[cfg(feature = "double-buffer")]
+    front: D::Buffer,
+}
+
+/// Region of the framebuffer to send, in framebuffer space (inclusive)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FlushWindow {
+    disp_width: u16,
+    upper_left: (u16, u16),
+    lower_right: (u16, u16),
+}
+
+/// Transfer of one framebuffer row
+#[derive(Debug, Clone)]
+struct RowSpan {
+    /// Hardware window to select before the pixels, opening a new memory write
+    area: Option<((u16, u16), (u16, u16))>,
+    /// Framebuffer pixels to send
+    pixels: Range<usize>,
+}
+
+/// One row of a frame queued by [`flush_queued`](Gc9a01::flush_queued), ready for a DMA
+/// transfer of up to `B` bytes
+#[cfg(feature = "queue")]
+#[derive(Debug, Clone)]
+pub struct QueuedRow<const B: usize> {
+    /// Hardware window (`start`, `end`, inclusive) to select before the pixels, by sending
+    /// `ColumnAddressSet(start.0, end.0)`, `RowAddressSet(start.1, end.1)` and `MemoryWrite`.
+    /// `None` continues the memory write of the previous row.
+    pub window: Option<((u16, u16), (u16, u16))>,
+    /// Pixels in wire order for the pixel format of the display, big-endian Rgb565 unless
+    /// changed by `set_pixel_format`
+    pub bytes: heapless::Vec<u8, B>,
+}
+
+impl FlushWindow {
+    /// Log the size of the window about to be flushed
+    #[cfg(any(feature = "trace-flush", feature = "trace-flush-defmt"))]
+    fn trace(&self) {
+        let rows = self.lower_right.1 - self.upper_left.1 + 1;
+        let cols = self.lower_right.0 - self.upper_left.0 + 1;
+        let words = u32::from(rows) * u32::from(cols);
+
+        #[cfg(feature = "trace-flush")]
+        log::trace!("gc9a01 flush: {rows} rows x {cols} cols, {words} words");
+        #[cfg(feature = "trace-flush-defmt")]
+        defmt::trace!(
+            "gc9a01 flush: {} rows x {} cols, {} words",
+            rows,
+            cols,
+            words
+        );
+    }
 }
 
 impl<D> BufferedGraphics<D>
@@ -42,7 +145,64 @@ where
             max_x: u16::MIN,
             min_y: u16::MAX,
             max_y: u16::MIN,
+            all_dirty: false,
+            drawn: None,
+            transform: None,
+            clip: None,
+            bus_rate_hz: DEFAULT_BUS_RATE_HZ,
+            present: None,
+            #[cfg(feature = "nb")]
+            pending_flush: None,
+            #[cfg(feature = "queue")]
+            queued_flush: None,
+            #[cfg(feature = "double-buffer")]
+            front: NewZeroed::new_zeroed(),
+        }
+    }
+
+    /// Record the framebuffer `pixels` as sent to the panel
+    #[cfg(feature = "double-buffer")]
+    fn sync_front(&mut self, pixels: Range<usize>) {
+        self.front.as_mut()[pixels.clone()].copy_from_slice(&self.buffer.as_ref()[pixels]);
+    }
+
+    /// First run of changed pixels of the framebuffer row starting at `line`, between columns
+    /// `from` and `to` (inclusive)
+    ///
+    /// Runs closer than [`DIFF_MERGE_GAP`] unchanged pixels are merged.
+    #[cfg(feature = "double-buffer")]
+    fn next_run(&self, line: usize, from: u16, to: u16) -> Option<(u16, u16)> {
+        let (back, front) = (self.buffer.as_ref(), self.front.as_ref());
+        let changed = |column: u16| back[line + column as usize] != front[line + column as usize];
+
+        let start = (from..=to).find(|&column| changed(column))?;
+        let mut end = start;
+        for column in start + 1..=to {
+            if changed(column) {
+                end = column;
+            } else if column - end > DIFF_MERGE_GAP {
+                break;
+            }
+        }
+
+        Some((start, end))
+    }
+
+    /// Grow the dirty box to include the window from `start` to `end`
+    pub(crate) fn mark_dirty(&mut self, start: (u16, u16), end: (u16, u16)) {
+        self.min_x = self.min_x.min(start.0);
+        self.max_x = self.max_x.max(end.0);
+        self.min_y = self.min_y.min(start.1);
+        self.max_y = self.max_y.max(end.1);
+    }
+
+    /// Upper left and lower right corners of the dirty box, `None` if nothing was touched
+    const fn dirty_box(&self) -> Option<((u16, u16), (u16, u16))> {
+        if self.min_x > self.max_x || self.min_y > self.max_y {
+            return None;
         }
+
+        Some(((self.min_x, self.min_y), (self.max_x, self.max_y)))
     }
 }
 
@@ -61,7 +221,9 @@ where
 
     /// Initialise and clear the display in graphics mode.
     fn init(&mut self, delay: &mut DELAY) -> Result<(), DisplayError> {
+        // the panel content is unknown after power up
         self.clear();
+        self.mark_all_dirty();
         self.init_with_addr_mode(delay)
     }
 }
@@ -73,16 +235,18 @@ where
 {
     /// Clear the display buffer
     /// NOTE: Must use `flush` to apply changes
+    ///
+    /// Only the area drawn since the previous `clear` is marked dirty, so the next `flush`
+    /// wipes what was drawn instead of sending the full frame. Use
+    /// [`mark_all_dirty`](Self::mark_all_dirty) to clear a panel whose content is unknown.
     pub fn clear(&mut self) {
         for b in self.mode.buffer.as_mut() {
             *b = 0;
         }
 
-        let (max_x, max_y) = self.dimensions();
-        self.mode.min_x = u16::MIN;
-        self.mode.max_x = max_x;
-        self.mode.min_y = u16::MIN;
-        self.mode.max_y = max_y;
+        if let Some((start, end)) = self.mode.drawn.take() {
+            self.mode.mark_dirty(start, end);
+        }
     }
 
     pub fn fill(&mut self, color: u16) {
@@ -90,205 +254,2133 @@ where
             *b = color;
         }
 
-        let (max_x, max_y) = self.dimensions();
+        self.mark_all_dirty();
+    }
+
+    /// Fill the display buffer with an embedded-graphics color.
+    ///
+    /// This is [`fill`](Self::fill) without handling the raw Rgb565 value by hand.
+    /// NOTE: Must use `flush` to apply changes
+    #[cfg(feature = "graphics")]
+    pub fn fill_color(&mut self, color: Rgb565) {
+        self.fill(RawU16::from(color).into_inner());
+    }
+
+    /// Draw a bring-up test pattern into the display buffer: red, green, blue and white
+    /// quadrants (upper left, upper right, lower left, lower right) crossed by a black 1px
+    /// border and diagonal.
+    /// NOTE: Must use `flush` to apply changes
+    ///
+    /// Red and blue exchanged point at the color order, a cut or shifted border at the
+    /// offsets, and misplaced quadrants at the rotation. The transform set by
+    /// [`set_transform`](Self::set_transform) is ignored, but the clip set by
+    /// [`set_clip`](Self::set_clip) silently cuts the pattern.
+    pub fn draw_test_pattern(&mut self) {
+        const RED: u16 = 0xF800;
+        const GREEN: u16 = 0x07E0;
+        const BLUE: u16 = 0x001F;
+        const WHITE: u16 = 0xFFFF;
+        const BLACK: u16 = 0x0000;
+
+        let (width, height) = self.dimensions();
+        let (width, height) = (u32::from(width), u32::from(height));
+        self.mark_all_dirty();
+
+        for y in 0..height {
+            for x in 0..width {
+                let border = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+                // within half a pixel of the line from the upper left to the lower right
+                // corner, along its major axis
+                let diagonal =
+                    2 * (x * (height - 1)).abs_diff(y * (width - 1)) < width.max(height) - 1;
+
+                let color = if border || diagonal {
+                    BLACK
+                } else {
+                    match (x < width / 2, y < height / 2) {
+                        (true, true) => RED,
+                        (false, true) => GREEN,
+                        (true, false) => BLUE,
+                        (false, false) => WHITE,
+                    }
+                };
+                self.set_pixel_raw(x, y, color);
+            }
+        }
+    }
+
+    /// Mark the whole display dirty, so the next `flush` sends the full frame, in the rotation
+    /// set at that time.
+    ///
+    /// Until that flush, pixel writes skip the dirty box bookkeeping entirely, which saves
+    /// four comparisons per pixel when repainting everything.
+    pub const fn mark_all_dirty(&mut self) {
+        let (max_x, max_y) = self.bounds();
         self.mode.min_x = u16::MIN;
         self.mode.max_x = max_x;
         self.mode.min_y = u16::MIN;
         self.mode.max_y = max_y;
+        self.mode.all_dirty = true;
     }
 
     /// Write the display buffer
     ///
+    /// A frame left unfinished by `flush_nb` or [`begin_present`](Self::begin_present) is
+    /// completed first.
+    ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
     pub fn flush(&mut self) -> Result<(), DisplayError> {
-        // check if you touch anything
-        if self.mode.max_x < self.mode.min_x || self.mode.max_y < self.mode.min_y {
+        self.finish_pending()?;
+
+        let Some(window) = self.take_flush_window() else {
             return Ok(());
+        };
+
+        for row in window.upper_left.1..=window.lower_right.1 {
+            self.flush_row(&window, row)?;
         }
 
-        let (bound_width, bound_height) = self.bounds();
-        let (screen_width, screen_height) = self.dimensions();
+        Ok(())
+    }
 
-        // Determine witch bytes need to be sent
-        let disp_min_x = self.mode.min_x;
-        let disp_min_y = self.mode.min_y;
+    /// Draw a whole frame: [`clear`](Self::clear) the buffer, draw it with `f`, then
+    /// [`flush`](Self::flush).
+    ///
+    /// ```ignore
+    /// display.present(|display| {
+    ///     Circle::new(Point::new(80, 80), 80)
+    ///         .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+    ///         .draw(display)
+    /// })?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns the error of `f`, in which case nothing is flushed, and may return
+    /// an error if there are communication issues with the display.
+    pub fn present<F>(&mut self, f: F) -> Result<(), DisplayError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), DisplayError>,
+    {
+        self.clear();
+        f(self)?;
 
-        let (disp_max_x, disp_max_y) = (
-            (self.mode.max_x).min(bound_width),
-            (self.mode.max_y).min(bound_height),
-        );
+        self.flush()
+    }
 
-        // reset idle state
-        self.mode.min_x = u16::MAX;
-        self.mode.max_x = u16::MIN;
-        self.mode.min_y = u16::MAX;
-        self.mode.max_y = u16::MIN;
+    /// Write the display buffer through `f`, e.g. to start a DMA transfer.
+    ///
+    /// The draw window and the memory write command (2Ah/2Bh/2Ch) are sent through the
+    /// interface, then `f` is called with consecutive chunks of the pixels in wire order
+    /// (big-endian Rgb565), staged in a scratch buffer of up to 256 bytes. `f` has to send
+    /// them as data (DC high) and complete the transfer before returning: the chunk is
+    /// reused for the next call.
+    ///
+    /// To fit one window, the pixels are whole framebuffer rows covering the dirty box, even
+    /// the invisible corners of round panels. A frame left unfinished by `flush_nb` or
+    /// [`begin_present`](Self::begin_present) is completed first.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::DataFormatNotImplemented`] unless the pixel format
+    /// is 16-bit, and may return an error if there are communication issues with the display,
+    /// or the error returned by `f`.
+    pub fn flush_with<F>(&mut self, mut f: F) -> Result<(), DisplayError>
+    where
+        F: FnMut(&[u8]) -> Result<(), DisplayError>,
+    {
+        if self.pixel_format != Dbi::Pixel16bits {
+            return Err(DisplayError::DataFormatNotImplemented);
+        }
 
-        let offset_x = match self.display_rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate270 => D::OFFSET_X,
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate180 => {
-                D::COLS - D::WIDTH - D::OFFSET_X
-            }
+        self.finish_pending()?;
+
+        let Some(window) = self.take_flush_window() else {
+            return Ok(());
         };
 
-        match self.display_rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
-                self.set_draw_area(
-                    (disp_min_x + offset_x, disp_min_y + D::OFFSET_Y),
-                    (disp_max_x + offset_x, disp_max_y + D::OFFSET_Y),
-                )?;
-
-                Self::flush_buffer_chunks(
-                    &mut self.interface,
-                    self.mode.buffer.as_mut(),
-                    screen_width as usize,
-                    (disp_min_x, disp_min_y),
-                    (disp_max_x, disp_max_y),
-                )
-            }
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
-                self.set_draw_area(
-                    (disp_min_y + offset_x, disp_min_x + D::OFFSET_Y),
-                    (disp_max_y + offset_x, disp_max_x + D::OFFSET_Y),
-                )?;
-
-                Self::flush_buffer_chunks(
-                    &mut self.interface,
-                    self.mode.buffer.as_mut(),
-                    screen_height as usize,
-                    (disp_min_y, disp_min_x),
-                    (disp_max_y, disp_max_x),
-                )
+        let (offset_x, offset_y) = self.window_offset();
+        let (first, last) = (window.upper_left.1, window.lower_right.1);
+        self.set_draw_area(
+            (offset_x, first + offset_y),
+            (window.disp_width - 1 + offset_x, last + offset_y),
+        )?;
+        self.set_write_mode()?;
+
+        let width = window.disp_width as usize;
+        let range = first as usize * width..(last as usize + 1) * width;
+        #[cfg(feature = "double-buffer")]
+        let sent = range.clone();
+
+        let mut scratch = [0u8; PIXEL_SCRATCH_LEN * 2];
+        for pixels in self.mode.buffer.as_ref()[range].chunks(PIXEL_SCRATCH_LEN) {
+            let bytes = &mut scratch[..pixels.len() * 2];
+            for (wire, pixel) in bytes.chunks_exact_mut(2).zip(pixels) {
+                wire.copy_from_slice(&pixel.to_be_bytes());
             }
+
+            f(bytes)?;
         }
+
+        #[cfg(feature = "double-buffer")]
+        self.mode.sync_front(sent);
+
+        Ok(())
     }
 
-    /// Set the pixels
+    /// Write only the runs of pixels that changed since they were last sent.
+    ///
+    /// Within the dirty box, every row is compared with the front buffer, a copy of what
+    /// every flush sent, and each run of changed pixels gets its own window. Runs separated
+    /// by fewer unchanged pixels than the window commands cost are merged. Scattered changes,
+    /// e.g. a cursor moving away from an animation, no longer pull in the whole bounding box
+    /// between them.
+    ///
+    /// After [`mark_all_dirty`](Self::mark_all_dirty) (as done by init) the panel content is
+    /// unknown, and the whole frame is sent.
     ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
-    /// This method may return an error if there are an out of bounds error.
-    pub fn set_pixels<T>(
-        &mut self,
-        start: (u16, u16),
-        end: (u16, u16),
-        colors: T,
-    ) -> Result<(), DisplayError>
-    where
-        T: IntoIterator<Item = u16>,
-    {
-        let x = start.0;
-        let y = start.1;
-        let rotation = self.display_rotation;
+    #[cfg(feature = "double-buffer")]
+    pub fn flush_diff(&mut self) -> Result<(), DisplayError> {
+        if self.mode.all_dirty {
+            return self.flush();
+        }
 
-        let idx = match rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
-                ((y as usize) * D::WIDTH as usize) + (x as usize)
-            }
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
-                ((x as usize) * D::HEIGHT as usize) + (y as usize)
-            }
+        self.finish_pending()?;
+
+        let Some(window) = self.take_flush_window() else {
+            return Ok(());
         };
 
-        let mut buffer_index = idx;
-        let buffer_len = self.mode.buffer.as_mut().len();
+        for row in window.upper_left.1..=window.lower_right.1 {
+            let line = row as usize * window.disp_width as usize;
+            let mut from = window.upper_left.0;
 
-        for color in colors {
-            if buffer_index >= buffer_len {
-                return Err(DisplayError::OutOfBoundsError);
-            }
+            while let Some((start, end)) = self.mode.next_run(line, from, window.lower_right.0) {
+                let run = FlushWindow {
+                    disp_width: window.disp_width,
+                    upper_left: (start, row),
+                    lower_right: (end, row),
+                };
+                self.flush_row(&run, row)?;
 
-            // Directly copy the color into the buffer
-            unsafe {
-                *self.mode.buffer.as_mut().get_unchecked_mut(buffer_index) = color;
+                if end == window.lower_right.0 {
+                    break;
+                }
+                from = end + 1;
             }
-            buffer_index += 1;
         }
 
-        self.mode.min_x = self.mode.min_x.min(start.0);
-        self.mode.max_x = self.mode.max_x.max(end.0);
-        self.mode.min_y = self.mode.min_y.min(start.1);
-        self.mode.max_y = self.mode.max_y.max(end.1);
-
         Ok(())
     }
 
-    /// Set a pixel color. If the X and Y coordinates are out of the bounds
-    /// of the display, this method call is a noop
-    pub fn set_pixel(&mut self, x: u32, y: u32, value: u16) {
-        let rotation = self.display_rotation;
+    /// Write only the pixels that changed since they were last sent, then make the drawn
+    /// frame the one on the panel.
+    ///
+    /// The framebuffer is the back buffer: draw the whole next frame into it, e.g. clear then
+    /// redraw, and nothing of the intermediate state reaches the panel. This compares it with
+    /// the front buffer, a copy of what every flush sent, and shrinks the dirty box to the
+    /// bounding box of the differences before flushing it. Mostly static scenes only cost the
+    /// comparison. The back buffer keeps its content, so the next frame can be drawn
+    /// incrementally.
+    ///
+    /// After [`mark_all_dirty`](Self::mark_all_dirty) (as done by init) the panel content is
+    /// unknown, and the whole frame is sent. Call it after changing the rotation too, which
+    /// moves the framebuffer pixels on the panel.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    #[cfg(feature = "double-buffer")]
+    pub fn swap_and_flush(&mut self) -> Result<(), DisplayError> {
+        if self.mode.all_dirty {
+            return self.flush();
+        }
 
-        let idx = match rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
-                ((y as usize) * D::WIDTH as usize) + (x as usize)
-            }
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
-                ((x as usize) * D::HEIGHT as usize) + (y as usize)
+        self.finish_pending()?;
+
+        let Some(window) = self.take_flush_window() else {
+            return Ok(());
+        };
+
+        let width = window.disp_width as usize;
+        let (back, front) = (self.mode.buffer.as_ref(), self.mode.front.as_ref());
+        let mut changed: Option<((u16, u16), (u16, u16))> = None;
+        for row in window.upper_left.1..=window.lower_right.1 {
+            let line = row as usize * width;
+            for column in window.upper_left.0..=window.lower_right.0 {
+                let idx = line + column as usize;
+                if back[idx] != front[idx] {
+                    changed = Some(
+                        changed.map_or(((column, row), (column, row)), |(from, to)| {
+                            ((from.0.min(column), from.1), (to.0.max(column), row))
+                        }),
+                    );
+                }
             }
+        }
+
+        let Some((upper_left, lower_right)) = changed else {
+            return Ok(());
         };
 
-        if let Some(color) = self.mode.buffer.as_mut().get_mut(idx) {
-            self.mode.min_x = self.mode.min_x.min(x as u16);
-            self.mode.max_x = self.mode.max_x.max(x as u16);
-            self.mode.min_y = self.mode.min_y.min(y as u16);
-            self.mode.max_y = self.mode.max_y.max(y as u16);
+        let window = FlushWindow {
+            disp_width: window.disp_width,
+            upper_left,
+            lower_right,
+        };
+        for row in window.upper_left.1..=window.lower_right.1 {
+            self.flush_row(&window, row)?;
+        }
 
-            *color = value;
+        Ok(())
+    }
+
+    /// Complete a frame left unfinished by `flush_nb` or `begin_present`
+    fn finish_pending(&mut self) -> Result<(), DisplayError> {
+        self.end_present()?;
+
+        #[cfg(feature = "nb")]
+        if let Some((window, next_row)) = self.mode.pending_flush.take() {
+            for row in next_row..=window.lower_right.1 {
+                self.flush_row(&window, row)?;
+            }
         }
+
+        Ok(())
     }
-}
 
-#[cfg(feature = "graphics")]
-use embedded_graphics_core::{
-    draw_target::DrawTarget,
-    geometry::Size,
-    geometry::{Dimensions, OriginDimensions},
-    pixelcolor::raw::RawU16,
-    pixelcolor::Rgb565,
-    prelude::RawData,
-    Pixel,
-};
+    /// Write the display buffer and return the area that was sent, in display coordinates.
+    ///
+    /// Returns `None` if nothing was dirty. A frame left unfinished by `flush_nb` is completed
+    /// first but is not part of the returned area.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    #[cfg(feature = "graphics")]
+    pub fn flush_rect(&mut self) -> Result<Option<Rectangle>, DisplayError> {
+        let area = self.dirty_bounds();
 
-#[cfg(feature = "graphics")]
-impl<I, D> OriginDimensions for Gc9a01<I, D, BufferedGraphics<D>>
-where
-    I: WriteOnlyDataCommand,
-    D: DisplayDefinition,
-{
-    fn size(&self) -> Size {
-        let (w, h) = self.dimensions();
-        Size::new(w.into(), h.into())
+        self.flush()?;
+
+        Ok(area)
     }
-}
 
-#[cfg(feature = "graphics")]
-impl<I, D> DrawTarget for Gc9a01<I, D, BufferedGraphics<D>>
-where
-    I: WriteOnlyDataCommand,
-    D: DisplayDefinition,
-{
-    // TODO: figure out a way to handle all case
-    type Color = Rgb565;
-    type Error = DisplayError;
+    /// The area the next [`flush`](Self::flush) will send, in display coordinates, or `None`
+    /// if nothing is dirty.
+    ///
+    /// Check it to skip the flush of idle frames. A frame left unfinished by `flush_nb` is
+    /// not part of it.
+    #[cfg(feature = "graphics")]
+    pub fn dirty_bounds(&self) -> Option<Rectangle> {
+        self.dirty_area().map(|(top_left, bottom_right)| {
+            Rectangle::with_corners(
+                Point::new(top_left.0.into(), top_left.1.into()),
+                Point::new(bottom_right.0.into(), bottom_right.1.into()),
+            )
+        })
+    }
 
-    fn draw_iter<O>(&mut self, pixels: O) -> Result<(), Self::Error>
+    /// The bytes the next [`flush`](Self::flush) will send after the memory write, in send
+    /// order, e.g. to feed a custom transport.
+    ///
+    /// Rotation is already applied: the bytes follow the framebuffer rows of the dirty box as
+    /// big-endian Rgb565, the 16-bit pixel format. On round panels the rows are clipped to
+    /// the visible circle, and each of them gets its own window. A frame left unfinished by
+    /// `flush_nb` is not part of it, and the dirty tracking is left untouched.
+    pub fn wire_iter(&self) -> impl Iterator<Item = u8> + '_ {
+        let buffer = self.mode.buffer.as_ref();
+
+        self.flush_window()
+            .into_iter()
+            .flat_map(move |window| {
+                (window.upper_left.1..=window.lower_right.1)
+                    .filter_map(move |row| self.row_span(&window, row))
+            })
+            .flat_map(move |span| {
+                buffer[span.pixels]
+                    .iter()
+                    .flat_map(|pixel| pixel.to_be_bytes())
+            })
+    }
+
+    /// Wait for the rising edge of the tearing effect output on `te`, then write the display
+    /// buffer.
+    ///
+    /// The panel raises TE when it starts its vertical blanking, so starting the memory write
+    /// right after the edge keeps it ahead of the refresh scan and the frame does not tear.
+    /// The TE output is enabled by the init sequence (35h). The pin is polled every few
+    /// microseconds with `delay`: first until it is low, so an edge already in progress is
+    /// not taken for the start of the blanking, then until it is high. Each wait gives up
+    /// after a few frames, so a TE output turned off or left unwired can't hang the caller.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::BusWriteError`] without sending anything if `te`
+    /// can't be read or does not toggle in time, since `DisplayError` has no pin or timeout
+    /// variant. It may also return an error if there are communication issues with the
+    /// display.
+    pub fn flush_synced<TE, DELAY>(
+        &mut self,
+        te: &mut TE,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError>
     where
-        O: IntoIterator<Item = Pixel<Self::Color>>,
+        TE: InputPin,
+        DELAY: DelayNs,
     {
-        let bb = self.bounding_box();
+        for high in [false, true] {
+            let mut waited = 0;
+            while te.is_high().map_err(|_pin| DisplayError::BusWriteError)? != high {
+                if waited >= TE_TIMEOUT_US {
+                    return Err(DisplayError::BusWriteError);
+                }
+                delay.delay_us(TE_POLL_US);
+                waited += TE_POLL_US;
+            }
+        }
+
+        self.flush()
+    }
+
+    /// Write exactly `area` of the display buffer, whatever the dirty box.
+    ///
+    /// This updates a small region (e.g. a clock) at high frequency without sending the rest
+    /// of the dirty box. The area is clipped to the display and the dirty box is left
+    /// untouched, so the region is sent again by the next `flush` if it was dirty.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    #[cfg(feature = "graphics")]
+    pub fn flush_region(&mut self, area: Rectangle) -> Result<(), DisplayError> {
+        let (width, height) = self.dimensions();
+        let area = area.intersection(&Rectangle::new(
+            Point::zero(),
+            Size::new(width.into(), height.into()),
+        ));
+
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+
+        #[allow(clippy::cast_sign_loss)]
+        let window = self.window_for(
+            (area.top_left.x as u16, area.top_left.y as u16),
+            (bottom_right.x as u16, bottom_right.y as u16),
+        );
+
+        for row in window.upper_left.1..=window.lower_right.1 {
+            self.flush_row(&window, row)?;
+        }
 
-        pixels
-            .into_iter()
-            .filter(|&Pixel(pos, _color)| bb.contains(pos))
-            .for_each(|Pixel(pos, color)| {
-                let color: RawU16 = color.into();
-                let color: u16 = color.into_inner();
-                #[allow(clippy::cast_sign_loss)]
-                self.set_pixel(pos.x as u32, pos.y as u32, color);
-            });
         Ok(())
     }
+
+    /// Write the display buffer only if the transfer can complete before `deadline_ticks`.
+    ///
+    /// The transfer time is estimated from the dirty box and the bus rate set with
+    /// [`set_bus_rate`](Self::set_bus_rate). When the frame would miss the deadline nothing is
+    /// sent, the dirty box is preserved and `false` is returned, so an animation loop can drop
+    /// the frame instead of stretching it.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn flush_before<C>(&mut self, deadline_ticks: u64, clock: &C) -> Result<bool, DisplayError>
+    where
+        C: Clock,
+    {
+        let bits = self.flush_window().map_or(0, |window| {
+            let rows = u64::from(window.lower_right.1 - window.upper_left.1) + 1;
+            let cols = u64::from(window.lower_right.0 - window.upper_left.0) + 1;
+
+            rows * (cols * 16 + WINDOW_OVERHEAD_BITS)
+        });
+        let ticks = bits * C::TICKS_PER_SECOND / u64::from(self.mode.bus_rate_hz.max(1));
+
+        if clock.now().saturating_add(ticks) > deadline_ticks {
+            return Ok(false);
+        }
+
+        self.flush()?;
+
+        Ok(true)
+    }
+
+    /// Set the bus rate used by [`flush_before`](Self::flush_before) to estimate transfer
+    /// times, in bits per second (the SPI clock frequency).
+    pub const fn set_bus_rate(&mut self, hz: u32) {
+        self.mode.bus_rate_hz = hz;
+    }
+
+    /// Write the display buffer one row at a time, without blocking the caller for the
+    /// whole frame.
+    ///
+    /// Each call sends a single row of the dirty region and returns
+    /// [`nb::Error::WouldBlock`] while rows remain, resuming where it left off on the next
+    /// call. `Ok(())` is returned once the frame is complete (or if nothing was dirty). This
+    /// lets a superloop or cooperative scheduler interleave other work with the transfer.
+    ///
+    /// Pixels drawn while a frame is in progress are picked up by the next frame. No other
+    /// command must be sent to the display until the frame is complete; calling
+    /// [`flush`](Self::flush) completes it.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display,
+    /// in which case the rest of the frame is dropped.
+    #[cfg(feature = "nb")]
+    pub fn flush_nb(&mut self) -> nb::Result<(), DisplayError> {
+        let (window, row) = match self.mode.pending_flush.take() {
+            Some(pending) => pending,
+            None => match self.take_flush_window() {
+                Some(window) => (window, window.upper_left.1),
+                None => return Ok(()),
+            },
+        };
+
+        self.flush_row(&window, row)?;
+
+        if row < window.lower_right.1 {
+            self.mode.pending_flush = Some((window, row + 1));
+            return Err(nb::Error::WouldBlock);
+        }
+
+        Ok(())
+    }
+
+    /// Start streaming the whole framebuffer while it is still being drawn.
+    ///
+    /// The frame is sent top to bottom by [`present_rows`](Self::present_rows) as drawing
+    /// progresses, then completed by [`end_present`](Self::end_present), so the transfer of
+    /// the upper rows overlaps the rendering of the lower ones. Rows are framebuffer rows:
+    /// display rows, or display columns with [`DisplayRotation::Rotate90`] and
+    /// [`DisplayRotation::Rotate270`].
+    ///
+    /// Draw top to bottom: a pixel written into a row that was already sent does not reach the
+    /// display during this present, but stays dirty so the next [`flush`](Self::flush) sends
+    /// it. No other command may be sent until the present ends.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn begin_present(&mut self) -> Result<(), DisplayError> {
+        self.end_present()?;
+
+        // the whole frame is about to be sent
+        self.mark_all_dirty();
+        self.reset_dirty();
+
+        let (max_x, max_y) = self.bounds();
+        let window = self.window_for((0, 0), (max_x, max_y));
+        self.mode.present = Some((window, window.upper_left.1));
+
+        Ok(())
+    }
+
+    /// Send the framebuffer rows of the current present up to `rows` (exclusive).
+    ///
+    /// Rows already sent are skipped and `rows` past the last row are clamped, so this can be
+    /// called with the progress of the rendering. Does nothing outside of a present.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display,
+    /// in which case the rest of the present is dropped.
+    pub fn present_rows(&mut self, rows: u16) -> Result<(), DisplayError> {
+        let Some((window, next_row)) = self.mode.present.take() else {
+            return Ok(());
+        };
+
+        self.settle_present(next_row);
+
+        let end = rows.min(window.lower_right.1 + 1).max(next_row);
+        for row in next_row..end {
+            self.flush_row(&window, row)?;
+        }
+
+        self.mode.present = Some((window, end));
+
+        Ok(())
+    }
+
+    /// Send the remaining rows of the current present and end it.
+    ///
+    /// Does nothing outside of a present.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    pub fn end_present(&mut self) -> Result<(), DisplayError> {
+        self.present_rows(u16::MAX)?;
+        self.mode.present = None;
+
+        Ok(())
+    }
+
+    /// Drop the dirty box if the present still has to send all of it from `next_row` on
+    fn settle_present(&mut self, next_row: u16) {
+        if self
+            .flush_window()
+            .is_some_and(|window| window.upper_left.1 >= next_row)
+        {
+            self.reset_dirty();
+        }
+    }
+
+    /// Queue the rows of the display buffer for a transmitter draining `queue`, e.g. a DMA
+    /// complete interrupt.
+    ///
+    /// Rows are read from the framebuffer and swapped to wire order here, decoupled from the
+    /// SPI timing: the transmitter only sends each [`QueuedRow`] in order. When the queue is
+    /// full this returns `Ok(false)` so the caller can yield, and the next call resumes the
+    /// same frame. `Ok(true)` means the whole frame is queued. Nothing may be drawn or sent
+    /// to the display until the queue is drained, since the transmitter owns the memory
+    /// write in progress.
+    ///
+    /// Before queuing a new frame, a frame left unfinished by `flush_nb` or
+    /// [`begin_present`](Self::begin_present) is completed through the interface.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if a row does not fit in `B`
+    /// bytes, in which case the rest of the frame is dropped. Size `B` for the longest display
+    /// side times the bytes per pixel of the pixel format: 2 for 16-bit, 3 for 18-bit and 1.5
+    /// for 12-bit, rounded up.
+    #[cfg(feature = "queue")]
+    pub fn flush_queued<const B: usize, const N: usize>(
+        &mut self,
+        queue: &mut heapless::spsc::Queue<QueuedRow<B>, N>,
+    ) -> Result<bool, DisplayError> {
+        let (window, mut row) = if let Some(pending) = self.mode.queued_flush.take() {
+            pending
+        } else {
+            self.finish_pending()?;
+
+            match self.take_flush_window() {
+                Some(window) => (window, window.upper_left.1),
+                None => return Ok(true),
+            }
+        };
+
+        while row <= window.lower_right.1 {
+            if queue.is_full() {
+                self.mode.queued_flush = Some((window, row));
+                return Ok(false);
+            }
+
+            if let Some(span) = self.row_span(&window, row) {
+                let mut bytes = heapless::Vec::new();
+                #[cfg(feature = "double-buffer")]
+                let sent = span.pixels.clone();
+                let mut pixels = self.mode.buffer.as_ref()[span.pixels].iter().copied();
+                let mut scratch = [0u8; PIXEL_SCRATCH_LEN * 3];
+                loop {
+                    match pack_pixels(self.pixel_format, &mut pixels, &mut scratch) {
+                        0 => break,
+                        len => bytes
+                            .extend_from_slice(&scratch[..len])
+                            .map_err(|()| DisplayError::OutOfBoundsError)?,
+                    }
+                }
+
+                // the transmitter moves the hardware window
+                if span.area.is_some() {
+                    self.draw_area = None;
+                }
+
+                queue
+                    .enqueue(QueuedRow {
+                        window: span.area,
+                        bytes,
+                    })
+                    .map_err(|_row| DisplayError::OutOfBoundsError)?;
+
+                #[cfg(feature = "double-buffer")]
+                self.mode.sync_front(sent);
+            }
+
+            row += 1;
+        }
+
+        Ok(true)
+    }
+
+    /// The dirty box in display coordinates of the current rotation, clamped to the display,
+    /// `None` if nothing was touched.
+    ///
+    /// After [`mark_all_dirty`](Self::mark_all_dirty) it is the whole display, whatever the
+    /// rotation set since.
+    const fn dirty_area(&self) -> Option<((u16, u16), (u16, u16))> {
+        let (bound_width, bound_height) = self.bounds();
+        if self.mode.all_dirty {
+            return Some(((0, 0), (bound_width, bound_height)));
+        }
+
+        match self.mode.dirty_box() {
+            Some(((min_x, min_y), (max_x, max_y))) => Some((
+                (min_x, min_y),
+                (
+                    if max_x < bound_width {
+                        max_x
+                    } else {
+                        bound_width
+                    },
+                    if max_y < bound_height {
+                        max_y
+                    } else {
+                        bound_height
+                    },
+                ),
+            )),
+            None => None,
+        }
+    }
+
+    /// The dirty box as a window in framebuffer space, `None` if nothing was touched.
+    fn flush_window(&self) -> Option<FlushWindow> {
+        self.dirty_area()
+            .map(|(upper_left, lower_right)| self.window_for(upper_left, lower_right))
+    }
+
+    /// Transform the display area from `upper_left` to `lower_right` (inclusive) into a
+    /// window in framebuffer space.
+    const fn window_for(&self, upper_left: (u16, u16), lower_right: (u16, u16)) -> FlushWindow {
+        let (screen_width, screen_height) = self.dimensions();
+
+        match self.display_rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => FlushWindow {
+                disp_width: screen_width,
+                upper_left,
+                lower_right,
+            },
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => FlushWindow {
+                disp_width: screen_height,
+                upper_left: (upper_left.1, upper_left.0),
+                lower_right: (lower_right.1, lower_right.0),
+            },
+        }
+    }
+
+    /// Take the dirty box as a window in framebuffer space and reset the dirty tracking.
+    ///
+    /// Returns `None` if nothing was touched.
+    fn take_flush_window(&mut self) -> Option<FlushWindow> {
+        let window = self.flush_window();
+
+        #[cfg(any(feature = "trace-flush", feature = "trace-flush-defmt"))]
+        if let Some(window) = window {
+            window.trace();
+        }
+
+        self.reset_dirty();
+
+        window
+    }
+
+    /// Reset the dirty tracking, remembering the dirty box as drawn for the next `clear`
+    fn reset_dirty(&mut self) {
+        // remember what reached the panel, for the next clear
+        if let Some((start, end)) = self.dirty_area() {
+            self.mode.drawn = Some(self.mode.drawn.map_or((start, end), |(from, to)| {
+                (
+                    (from.0.min(start.0), from.1.min(start.1)),
+                    (to.0.max(end.0), to.1.max(end.1)),
+                )
+            }));
+        }
+
+        // reset idle state
+        self.mode.min_x = u16::MAX;
+        self.mode.max_x = u16::MIN;
+        self.mode.min_y = u16::MAX;
+        self.mode.max_y = u16::MIN;
+        self.mode.all_dirty = false;
+    }
+
+    /// Plan the transfer of one framebuffer row of `window`.
+    ///
+    /// Rows must be sent in order: the first row of a rectangular window opens the memory
+    /// write that the following rows continue. On round panels each row is clipped to the
+    /// visible circle and opens its own memory write. Returns `None` if nothing of the row is
+    /// visible.
+    fn row_span(&self, window: &FlushWindow, row: u16) -> Option<RowSpan> {
+        let (offset_x, offset_y) = self.window_offset();
+        let line = row as usize * window.disp_width as usize;
+
+        let Some(insets) = D::VISIBLE_INSETS else {
+            // 12-bit pixels go in pairs: odd rows can't continue each other's memory write
+            let odd_pairs = self.pixel_format == Dbi::Pixel12bits
+                && (window.lower_right.0 - window.upper_left.0).is_multiple_of(2);
+
+            let area = if odd_pairs {
+                Some((
+                    (window.upper_left.0 + offset_x, row + offset_y),
+                    (window.lower_right.0 + offset_x, row + offset_y),
+                ))
+            } else {
+                (row == window.upper_left.1).then_some((
+                    (
+                        window.upper_left.0 + offset_x,
+                        window.upper_left.1 + offset_y,
+                    ),
+                    (
+                        window.lower_right.0 + offset_x,
+                        window.lower_right.1 + offset_y,
+                    ),
+                ))
+            };
+
+            return Some(RowSpan {
+                area,
+                pixels: line + window.upper_left.0 as usize
+                    ..line + window.lower_right.0 as usize + 1,
+            });
+        };
+
+        // Round panel: only send the part of the row inside the visible circle
+        let inset = insets.get(row as usize).copied().unwrap_or_default();
+        let first = window.upper_left.0.max(inset);
+        let last = window
+            .lower_right
+            .0
+            .min((window.disp_width - 1).saturating_sub(inset));
+
+        (first <= last).then_some(RowSpan {
+            area: Some((
+                (first + offset_x, row + offset_y),
+                (last + offset_x, row + offset_y),
+            )),
+            pixels: line + first as usize..line + last as usize + 1,
+        })
+    }
+
+    /// Send one framebuffer row of `window` to the display, see [`row_span`](Self::row_span).
+    fn flush_row(&mut self, window: &FlushWindow, row: u16) -> Result<(), DisplayError> {
+        let Some(span) = self.row_span(window, row) else {
+            return Ok(());
+        };
+
+        if let Some((start, end)) = span.area {
+            self.set_draw_area(start, end)?;
+            self.set_write_mode()?;
+        }
+
+        #[cfg(feature = "double-buffer")]
+        let sent = span.pixels.clone();
+        Self::send_pixels(
+            &mut self.interface,
+            self.pixel_format,
+            &self.mode.buffer.as_mut()[span.pixels],
+        )?;
+
+        #[cfg(feature = "double-buffer")]
+        self.mode.sync_front(sent);
+
+        Ok(())
+    }
+
+    /// Write the display buffer over an async interface.
+    ///
+    /// This is the async twin of [`flush`](Self::flush): every row is awaited, so other tasks
+    /// of the executor run while the frame is transferred. The interface has to implement
+    /// both the blocking and the async `display-interface` traits, as `SPIInterface` does over
+    /// an SPI device implementing both embedded-hal and embedded-hal-async.
+    ///
+    /// A frame left unfinished by `flush_nb` or [`begin_present`](Self::begin_present) is
+    /// completed first.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    #[cfg(feature = "async")]
+    pub async fn flush_async(&mut self) -> Result<(), DisplayError>
+    where
+        I: AsyncWriteOnlyDataCommand,
+    {
+        self.finish_pending_async().await?;
+
+        let Some(window) = self.take_flush_window() else {
+            return Ok(());
+        };
+
+        for row in window.upper_left.1..=window.lower_right.1 {
+            self.flush_row_async(&window, row).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Await the rising edge of the tearing effect output on `te`, then write the display
+    /// buffer over an async interface.
+    ///
+    /// This is the async twin of [`flush_synced`](Self::flush_synced): the edge is awaited
+    /// with `wait_for_rising_edge` instead of polling the pin. There is no timeout here, race
+    /// the future against a timer of the executor if TE may be off or unwired.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::BusWriteError`] if `te` can't be waited on, since
+    /// `DisplayError` has no pin variant, or may return an error if there are communication
+    /// issues with the display.
+    #[cfg(feature = "async")]
+    pub async fn flush_synced_async<TE>(&mut self, te: &mut TE) -> Result<(), DisplayError>
+    where
+        I: AsyncWriteOnlyDataCommand,
+        TE: Wait,
+    {
+        te.wait_for_rising_edge()
+            .await
+            .map_err(|_pin| DisplayError::BusWriteError)?;
+
+        self.flush_async().await
+    }
+
+    /// Async twin of [`finish_pending`](Self::finish_pending)
+    #[cfg(feature = "async")]
+    async fn finish_pending_async(&mut self) -> Result<(), DisplayError>
+    where
+        I: AsyncWriteOnlyDataCommand,
+    {
+        if let Some((window, next_row)) = self.mode.present.take() {
+            self.settle_present(next_row);
+            for row in next_row..=window.lower_right.1 {
+                self.flush_row_async(&window, row).await?;
+            }
+        }
+
+        #[cfg(feature = "nb")]
+        if let Some((window, next_row)) = self.mode.pending_flush.take() {
+            for row in next_row..=window.lower_right.1 {
+                self.flush_row_async(&window, row).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Async twin of [`flush_row`](Self::flush_row)
+    #[cfg(feature = "async")]
+    async fn flush_row_async(&mut self, window: &FlushWindow, row: u16) -> Result<(), DisplayError>
+    where
+        I: AsyncWriteOnlyDataCommand,
+    {
+        let Some(span) = self.row_span(window, row) else {
+            return Ok(());
+        };
+
+        if let Some((start, end)) = span.area {
+            self.set_draw_area_async(start, end).await?;
+            Command::MemoryWrite.send_async(&mut self.interface).await?;
+        }
+
+        #[cfg(feature = "double-buffer")]
+        let sent = span.pixels.clone();
+        Self::send_pixels_async(
+            &mut self.interface,
+            self.pixel_format,
+            &self.mode.buffer.as_mut()[span.pixels],
+        )
+        .await?;
+
+        #[cfg(feature = "double-buffer")]
+        self.mode.sync_front(sent);
+
+        Ok(())
+    }
+
+    /// Set the pixels of the window from `start` to `end` (inclusive) based on the `colors`
+    /// iterator, row by row.
+    ///
+    /// The window is checked against the [`dimensions`](Self::dimensions) of the current
+    /// rotation before anything is written. Pixels outside the clip set by
+    /// [`set_clip`](Self::set_clip) are skipped. Extra colors are ignored.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if `start` is not the upper left
+    /// corner of the window or the window does not fit on the display, leaving the buffer
+    /// untouched.
+    pub fn set_pixels<T>(
+        &mut self,
+        start: (u16, u16),
+        end: (u16, u16),
+        colors: T,
+    ) -> Result<(), DisplayError>
+    where
+        T: IntoIterator<Item = u16>,
+    {
+        let (width, height) = self.dimensions();
+        if start.0 > end.0 || start.1 > end.1 || end.0 >= width || end.1 >= height {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        let positions = (start.1..=end.1).flat_map(|y| (start.0..=end.0).map(move |x| (x, y)));
+        self.write_in_clip(positions.zip(colors));
+
+        if let Some((start, end)) = self.clip_window(start, end) {
+            self.mode.mark_dirty(start, end);
+        }
+
+        Ok(())
+    }
+
+    /// Set the pixels of the window from `start` to `end` (inclusive) from a column-major
+    /// `colors` iterator.
+    ///
+    /// The first `end.1 - start.1 + 1` colors fill the leftmost column from top to bottom,
+    /// the next ones fill the following column, and so on. The transpose into the row-major
+    /// framebuffer happens while writing, so no temporary buffer is needed. Pixels outside the
+    /// clip set by [`set_clip`](Self::set_clip) are skipped. Extra colors are ignored.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if the window is out of the display bounds.
+    pub fn set_pixels_columns<T>(
+        &mut self,
+        start: (u16, u16),
+        end: (u16, u16),
+        colors: T,
+    ) -> Result<(), DisplayError>
+    where
+        T: IntoIterator<Item = u16>,
+    {
+        let (width, height) = self.dimensions();
+        if start.0 > end.0 || start.1 > end.1 || end.0 >= width || end.1 >= height {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        let positions = (start.0..=end.0).flat_map(|x| (start.1..=end.1).map(move |y| (x, y)));
+        self.write_in_clip(positions.zip(colors));
+
+        if let Some((start, end)) = self.clip_window(start, end) {
+            self.mode.mark_dirty(start, end);
+        }
+
+        Ok(())
+    }
+
+    /// Set the pixels of the window from `start` to `end` (inclusive) from a row-major
+    /// `colors` iterator, clipping the window to the display bounds.
+    ///
+    /// The window may extend past any edge of the display: pixels falling outside of it, or
+    /// outside the clip set by [`set_clip`](Self::set_clip), are skipped and counted instead
+    /// of failing the whole write. Extra colors are ignored.
+    ///
+    /// Returns the number of clipped pixels, so `0` means the window fully fit in the clip.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if `start` is not the upper left corner of the window.
+    pub fn set_pixels_clipped<T>(
+        &mut self,
+        start: (i32, i32),
+        end: (i32, i32),
+        colors: T,
+    ) -> Result<usize, DisplayError>
+    where
+        T: IntoIterator<Item = u16>,
+    {
+        if start.0 > end.0 || start.1 > end.1 {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        let ((left, top), (right, bottom)) = self.clip_area();
+        let inside = |v: i32, from: u16, to: u16| v >= from.into() && v < to.into();
+        let positions = (start.1..=end.1).flat_map(|y| (start.0..=end.0).map(move |x| (x, y)));
+        let mut clipped = 0;
+
+        for ((x, y), color) in positions.zip(colors) {
+            if inside(x, left, right) && inside(y, top, bottom) {
+                #[allow(clippy::cast_sign_loss)]
+                let idx = self.pixel_index(x as u32, y as u32);
+                self.mode.buffer.as_mut()[idx] = color;
+            } else {
+                clipped += 1;
+            }
+        }
+
+        // only the visible part of the window is dirty
+        let corner = |v: i32, from: u16, to: u16| {
+            u16::try_from(v.clamp(from.into(), i32::from(to) - 1)).unwrap_or(0)
+        };
+        let visible = end.0 >= left.into()
+            && end.1 >= top.into()
+            && start.0 < right.into()
+            && start.1 < bottom.into();
+        if visible {
+            self.mode.mark_dirty(
+                (corner(start.0, left, right), corner(start.1, top, bottom)),
+                (corner(end.0, left, right), corner(end.1, top, bottom)),
+            );
+        }
+
+        Ok(clipped)
+    }
+
+    /// Set a pixel color. If the X and Y coordinates are out of the bounds
+    /// of the display, this method call is a noop
+    ///
+    /// The coordinates go through the transform set by [`set_transform`](Self::set_transform).
+    pub fn set_pixel(&mut self, x: u32, y: u32, value: u16) {
+        if let Some((x, y)) = self.transform_pixel(x, y) {
+            self.set_pixel_raw(x, y, value);
+        }
+    }
+
+    /// Get the framebuffer color of the pixel at `x` and `y` coordinates, or `None` if they
+    /// are out of the bounds of the display.
+    ///
+    /// The coordinates go through the same transform as [`set_pixel`](Self::set_pixel), so
+    /// a pixel reads back at the coordinates it was set.
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<u16> {
+        let (x, y) = self.transform_pixel(x, y)?;
+
+        self.mode
+            .buffer
+            .as_ref()
+            .get(self.pixel_index(x, y))
+            .copied()
+    }
+
+    /// Get the framebuffer color of the pixel at `x` and `y` coordinates as [`Rgb565`], see
+    /// [`get_pixel`](Self::get_pixel).
+    #[cfg(feature = "graphics")]
+    pub fn get_pixel_color(&self, x: u32, y: u32) -> Option<Rgb565> {
+        self.get_pixel(x, y).map(|raw| RawU16::new(raw).into())
+    }
+
+    /// Replace the whole framebuffer with `data`, e.g. a splash screen stored in flash, and
+    /// mark it dirty so the next `flush` shows it.
+    ///
+    /// `data` holds native Rgb565 values in framebuffer order, as written by
+    /// [`save_framebuffer`](Self::save_framebuffer): `D::HEIGHT` rows of `D::WIDTH` pixels,
+    /// transposed for [`DisplayRotation::Rotate90`] and [`DisplayRotation::Rotate270`].
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if `data` is not exactly
+    /// `D::WIDTH * D::HEIGHT` pixels.
+    pub fn load_framebuffer(&mut self, data: &[u16]) -> Result<(), DisplayError> {
+        let buffer = self.mode.buffer.as_mut();
+        if data.len() != buffer.len() {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        buffer.copy_from_slice(data);
+        self.mark_all_dirty();
+
+        Ok(())
+    }
+
+    /// Copy the whole framebuffer into `out`, see [`load_framebuffer`](Self::load_framebuffer).
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if `out` is not exactly
+    /// `D::WIDTH * D::HEIGHT` pixels.
+    pub fn save_framebuffer(&self, out: &mut [u16]) -> Result<(), DisplayError> {
+        let buffer = self.mode.buffer.as_ref();
+        if out.len() != buffer.len() {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        out.copy_from_slice(buffer);
+
+        Ok(())
+    }
+
+    /// Blend a color into the pixel at `x` and `y` coordinates instead of overwriting it.
+    ///
+    /// `alpha` is the coverage of `color`: `0` keeps the framebuffer content untouched and
+    /// `255` behaves like [`set_pixel`](Self::set_pixel). The blend is computed per channel in
+    /// the 5/6/5 space of the framebuffer. If the X and Y coordinates are out of the bounds
+    /// of the display, this method call is a noop
+    #[cfg(feature = "graphics")]
+    pub fn blend_pixel(&mut self, x: u32, y: u32, color: Rgb565, alpha: u8) {
+        let Some((x, y)) = self.transform_pixel(x, y) else {
+            return;
+        };
+
+        let idx = self.pixel_index(x, y);
+
+        if let Some(&current) = self.mode.buffer.as_mut().get(idx) {
+            let value = blend_rgb565(current, RawU16::from(color).into_inner(), alpha);
+            self.set_pixel_raw(x, y, value);
+        }
+    }
+
+    /// Draw an anti-aliased line from `start` to `end` using Xiaolin Wu's algorithm.
+    ///
+    /// Each step along the major axis blends `color` into the two pixels straddling the
+    /// ideal line, weighted by their distance to it, through [`blend_pixel`](Self::blend_pixel).
+    /// The math is fixed-point, so no FPU is needed. Parts of the line outside of the display
+    /// are ignored.
+    #[cfg(feature = "aa")]
+    pub fn draw_line_aa(&mut self, start: Point, end: Point, color: Rgb565) {
+        let steep = (end.y - start.y).abs() > (end.x - start.x).abs();
+        let (mut p0, mut p1) = if steep {
+            ((start.y, start.x), (end.y, end.x))
+        } else {
+            ((start.x, start.y), (end.x, end.y))
+        };
+        if p0.0 > p1.0 {
+            core::mem::swap(&mut p0, &mut p1);
+        }
+
+        let dx = i64::from(p1.0 - p0.0);
+        let dy = i64::from(p1.1 - p0.1);
+        // Q16.16 minor axis step per major axis step
+        let gradient = if dx == 0 { 0 } else { (dy << 16) / dx };
+        let mut intery = i64::from(p0.1) << 16;
+
+        let mut plot = |major: i32, minor: i64, alpha: i64| {
+            let (x, y) = if steep {
+                (minor, major.into())
+            } else {
+                (major.into(), minor)
+            };
+            if let (Ok(x), Ok(y), Ok(alpha)) =
+                (u32::try_from(x), u32::try_from(y), u8::try_from(alpha))
+            {
+                self.blend_pixel(x, y, color, alpha);
+            }
+        };
+
+        for major in p0.0..=p1.0 {
+            let minor = intery >> 16;
+            let coverage = (intery & 0xFFFF) >> 8;
+
+            plot(major, minor, 255 - coverage);
+            if coverage > 0 {
+                plot(major, minor + 1, coverage);
+            }
+
+            intery += gradient;
+        }
+    }
+
+    /// Fill the ring between `inner_r` and `outer_r` around `center` with `color`.
+    ///
+    /// A pixel belongs to the ring when its squared distance to `center` is at least
+    /// `inner_r²` and at most `outer_r²`, so an `inner_r` of `0` fills a disc. Rows are
+    /// computed with integer square roots and written straight into the framebuffer, and the
+    /// dirty box grows once by the ring bounding square. Pixels outside of the display are
+    /// ignored.
+    #[cfg(feature = "graphics")]
+    pub fn fill_ring(&mut self, center: Point, inner_r: u16, outer_r: u16, color: Rgb565) {
+        self.fill_ring_where(center, inner_r, outer_r, color, |_dx, _dy| true);
+    }
+
+    /// Fill the part of the ring between `inner_r` and `outer_r` around `center` swept
+    /// clockwise from `start_deg` to `end_deg`, like a progress indicator.
+    ///
+    /// Angles are in degrees, clockwise from 12 o'clock. Nothing is drawn if `end_deg` is not
+    /// after `start_deg`, and a sweep of 360 degrees or more fills the whole ring. See
+    /// [`fill_ring`](Self::fill_ring) for the ring geometry.
+    #[cfg(feature = "graphics")]
+    pub fn fill_arc(
+        &mut self,
+        center: Point,
+        inner_r: u16,
+        outer_r: u16,
+        start_deg: i32,
+        end_deg: i32,
+        color: Rgb565,
+    ) {
+        let sweep = end_deg.saturating_sub(start_deg);
+        if sweep <= 0 {
+            return;
+        }
+        if sweep >= 360 {
+            self.fill_ring(center, inner_r, outer_r, color);
+            return;
+        }
+
+        // unit vectors (Q16.16) pointing at both ends, 0 degrees is up with Y down
+        let direction = |deg: i32| {
+            (
+                i64::from(sin_degrees(deg)),
+                -i64::from(sin_degrees(deg.rem_euclid(360) + 90)),
+            )
+        };
+        let (from, to) = (direction(start_deg), direction(end_deg));
+        // positive when `b` lies clockwise of `a`, within half a turn
+        let cross = |a: (i64, i64), b: (i64, i64)| a.0 * b.1 - a.1 * b.0;
+
+        self.fill_ring_where(center, inner_r, outer_r, color, |dx, dy| {
+            let point = (i64::from(dx), i64::from(dy));
+
+            if sweep <= 180 {
+                cross(from, point) >= 0 && cross(point, to) >= 0
+            } else {
+                !(cross(to, point) > 0 && cross(point, from) > 0)
+            }
+        });
+    }
+
+    /// Fill the ring pixels around `center` for which `keep(dx, dy)` holds, `dx` and `dy`
+    /// being the offsets from `center`.
+    #[cfg(feature = "graphics")]
+    fn fill_ring_where<F>(
+        &mut self,
+        center: Point,
+        inner_r: u16,
+        outer_r: u16,
+        color: Rgb565,
+        keep: F,
+    ) where
+        F: Fn(i32, i32) -> bool,
+    {
+        let (start, end) = self.clip_area();
+        let value = RawU16::from(color).into_inner();
+        let (inner_sq, outer_sq) = (u32::from(inner_r).pow(2), u32::from(outer_r).pow(2));
+        let outer = i32::from(outer_r);
+
+        // saturate so far away centers give empty ranges, the offsets below then stay within
+        // `outer` of the center
+        let rows = center.y.saturating_sub(outer).max(start.1.into())
+            ..=center.y.saturating_add(outer).min(i32::from(end.1) - 1);
+        let cols = center.x.saturating_sub(outer).max(start.0.into())
+            ..=center.x.saturating_add(outer).min(i32::from(end.0) - 1);
+        if rows.is_empty() || cols.is_empty() {
+            return;
+        }
+
+        for y in rows.clone() {
+            let dy_sq = (y - center.y).unsigned_abs().pow(2);
+            // outermost offset inside the ring, and innermost offset outside of the hole
+            let reach = (outer_sq - dy_sq).isqrt().cast_signed();
+            let hole = inner_sq
+                .checked_sub(dy_sq)
+                .filter(|&gap| gap > 0)
+                .map_or(-1, |gap| (gap - 1).isqrt().cast_signed());
+
+            let span = (-reach).max(cols.start() - center.x)..=reach.min(cols.end() - center.x);
+            for dx in span.filter(|dx| dx.abs() > hole) {
+                let x = center.x + dx;
+                if !keep(dx, y - center.y) {
+                    continue;
+                }
+
+                #[allow(clippy::cast_sign_loss)]
+                let idx = self.pixel_index(x as u32, y as u32);
+                self.mode.buffer.as_mut()[idx] = value;
+            }
+        }
+
+        #[allow(clippy::cast_sign_loss)]
+        self.mode.mark_dirty(
+            (*cols.start() as u16, *rows.start() as u16),
+            (*cols.end() as u16, *rows.end() as u16),
+        );
+    }
+
+    /// Plot scattered pixels into the framebuffer.
+    ///
+    /// This is the buffered counterpart of the basic mode `draw_points`: every point goes
+    /// through [`set_pixel`](Self::set_pixel) semantics (transform, bounds) and the
+    /// display is updated on the next `flush`.
+    ///
+    /// # Errors
+    ///
+    /// This method does not fail in buffered mode; the `Result` mirrors the basic mode API.
+    #[cfg(feature = "graphics")]
+    pub fn draw_points<P>(&mut self, points: P) -> Result<(), DisplayError>
+    where
+        P: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        self.draw_iter(points)
+    }
+
+    /// Draw an embedded-graphics [`ImageRaw`] with its upper left corner at `top_left`.
+    ///
+    /// Pixels are decoded with the byte order declared by the image type, so assets stored
+    /// big-endian or little-endian both work without swapping them first.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if the image does not entirely
+    /// fit on the display.
+    #[cfg(feature = "image")]
+    pub fn draw_image_raw<'a, BO>(
+        &mut self,
+        top_left: Point,
+        image: &ImageRaw<'a, Rgb565, BO>,
+    ) -> Result<(), DisplayError>
+    where
+        BO: ByteOrder,
+        ImageRaw<'a, Rgb565, BO>: ImageDrawable<Color = Rgb565>,
+    {
+        self.check_area_fits(top_left, image.size())?;
+        image.draw(&mut self.translated(top_left))
+    }
+
+    /// Confine the pixel writes to `clip`, or to the whole display with `None`.
+    ///
+    /// While a clip is set, every write to the framebuffer discards the pixels outside of it,
+    /// from [`set_pixel`](Self::set_pixel) and the embedded-graphics `draw_iter` and fills to
+    /// bulk writes such as [`set_pixels`](Self::set_pixels),
+    /// [`clear_region`](Self::clear_region) or [`draw_bytes`](Self::draw_bytes), so the
+    /// dirty box never grows past it either. The clip is in display coordinates, after the
+    /// transform set by [`set_transform`](Self::set_transform).
+    #[cfg(feature = "graphics")]
+    pub fn set_clip(&mut self, clip: Option<Rectangle>) {
+        self.mode.clip = clip.map(|clip| {
+            // keep the far corner within i32, the part cut off is past the display anyway
+            let limit = |start: i32, len: u32| {
+                let room = i64::from(i32::MAX) - i64::from(start);
+                len.min(u32::try_from(room.min(i32::MAX.into())).unwrap_or(0))
+            };
+            let clip = Rectangle::new(
+                clip.top_left,
+                Size::new(
+                    limit(clip.top_left.x, clip.size.width),
+                    limit(clip.top_left.y, clip.size.height),
+                ),
+            );
+            let visible = clip.intersection(&self.bounding_box());
+            // the intersection lies on the display, its corners fit in u16
+            let corner = |x: i32, size: u32| {
+                u16::try_from(x).unwrap_or(0) + u16::try_from(size).unwrap_or(0)
+            };
+
+            (
+                (corner(visible.top_left.x, 0), corner(visible.top_left.y, 0)),
+                (
+                    corner(visible.top_left.x, visible.size.width),
+                    corner(visible.top_left.y, visible.size.height),
+                ),
+            )
+        });
+    }
+
+    /// Upper left and lower right (exclusive) corners of the area open to pixel writes
+    fn clip_area(&self) -> ((u16, u16), (u16, u16)) {
+        self.mode
+            .clip
+            .unwrap_or_else(|| ((0, 0), self.dimensions()))
+    }
+
+    /// Part of the window from `start` to `end` (inclusive) open to pixel writes, `None` if
+    /// it is entirely clipped
+    fn clip_window(&self, start: (u16, u16), end: (u16, u16)) -> Option<((u16, u16), (u16, u16))> {
+        let ((left, top), (right, bottom)) = self.clip_area();
+        let start = (start.0.max(left), start.1.max(top));
+        let end = (
+            end.0.min(right.checked_sub(1)?),
+            end.1.min(bottom.checked_sub(1)?),
+        );
+
+        (start.0 <= end.0 && start.1 <= end.1).then_some((start, end))
+    }
+
+    /// Write the `pixels`, positions on the display paired with their color, skipping the
+    /// ones outside the clip
+    fn write_in_clip(&mut self, pixels: impl Iterator<Item = ((u16, u16), u16)>) {
+        let ((left, top), (right, bottom)) = self.clip_area();
+
+        for ((x, y), color) in pixels {
+            if (left..right).contains(&x) && (top..bottom).contains(&y) {
+                let idx = self.pixel_index(x.into(), y.into());
+                self.mode.buffer.as_mut()[idx] = color;
+            }
+        }
+    }
+
+    /// Part of `area` open to pixel writes, see [`clip_area`](Self::clip_area)
+    #[cfg(feature = "graphics")]
+    fn visible_rect(&self, area: &Rectangle) -> Rectangle {
+        let (start, end) = self.clip_area();
+        area.intersection(&Rectangle::with_corners(
+            Point::new(start.0.into(), start.1.into()),
+            Point::new(i32::from(end.0) - 1, i32::from(end.1) - 1),
+        ))
+    }
+
+    /// Fill `area`, which must lie on the display, with `value` row slice by row slice, and
+    /// mark it dirty
+    #[cfg(feature = "graphics")]
+    fn fill_rect(&mut self, area: Rectangle, value: u16) {
+        let Some(bottom_right) = area.bottom_right() else {
+            return;
+        };
+
+        #[allow(clippy::cast_sign_loss)]
+        let (upper_left, lower_right) = (
+            (area.top_left.x as u16, area.top_left.y as u16),
+            (bottom_right.x as u16, bottom_right.y as u16),
+        );
+        let window = self.window_for(upper_left, lower_right);
+        let buffer = self.mode.buffer.as_mut();
+
+        for row in window.upper_left.1..=window.lower_right.1 {
+            let line = row as usize * window.disp_width as usize;
+            buffer[line + window.upper_left.0 as usize..=line + window.lower_right.0 as usize]
+                .fill(value);
+        }
+
+        self.mode.mark_dirty(upper_left, lower_right);
+    }
+
+    /// Fill `area` with a vertical gradient from `top` on its first row to `bottom` on its
+    /// last row, and mark it dirty.
+    /// NOTE: Must use `flush` to apply changes
+    ///
+    /// Each row gets one color, interpolated on the 5/6/5 channels with integer math and
+    /// rounded to the nearest level. The gradient spans the whole `area` even when part of it
+    /// is outside of the display or the clip, which are not drawn. The coordinates go through
+    /// the transform set by [`set_transform`](Self::set_transform).
+    #[cfg(feature = "graphics")]
+    pub fn fill_gradient(&mut self, area: Rectangle, top: Rgb565, bottom: Rgb565) {
+        let (top, bottom) = (
+            RawU16::from(top).into_inner(),
+            RawU16::from(bottom).into_inner(),
+        );
+        let span = area.size.height.saturating_sub(1);
+
+        for (step, y) in (0..area.size.height).zip(area.top_left.y..) {
+            let value = lerp_rgb565(top, bottom, step, span);
+            let row = Rectangle::new(
+                Point::new(area.top_left.x, y),
+                Size::new(area.size.width, 1),
+            );
+
+            if self.mode.transform.is_some() {
+                for pos in row.points() {
+                    if let Some((x, y)) = self.transform_point(pos.x, pos.y) {
+                        self.set_pixel_raw(x, y, value);
+                    }
+                }
+            } else {
+                self.fill_rect(self.visible_rect(&row), value);
+            }
+        }
+    }
+
+    /// Copy the image `src`, row-major raw Rgb565 rows of `width` pixels, into the display
+    /// buffer with its upper left corner at `top_left`, and mark the copied area dirty.
+    /// NOTE: Must use `flush` to apply changes
+    ///
+    /// Only the part of the image on the display and inside the clip is copied, so it can be
+    /// placed partly off-screen. Rows are copied as slices when the rotation keeps them
+    /// contiguous in the framebuffer. A trailing incomplete row of `src` is ignored. The
+    /// coordinates go through the transform set by [`set_transform`](Self::set_transform).
+    #[cfg(feature = "graphics")]
+    pub fn blit(&mut self, top_left: Point, width: u16, src: &[u16]) {
+        if width == 0 {
+            return;
+        }
+        let stride = usize::from(width);
+        let height = u32::try_from(src.len() / stride).unwrap_or(u32::MAX);
+        let area = Rectangle::new(top_left, Size::new(width.into(), height));
+
+        // source index of the image pixel at display coordinates `pos`
+        #[allow(clippy::cast_sign_loss)]
+        let source = |pos: Point| {
+            let offset = pos - top_left;
+            offset.y as usize * stride + offset.x as usize
+        };
+
+        if self.mode.transform.is_some() {
+            for pos in area.points() {
+                if let Some((x, y)) = self.transform_point(pos.x, pos.y) {
+                    self.set_pixel_raw(x, y, src[source(pos)]);
+                }
+            }
+            return;
+        }
+
+        let visible = self.visible_rect(&area);
+        let Some(bottom_right) = visible.bottom_right() else {
+            return;
+        };
+        let len = visible.size.width as usize;
+
+        for y in visible.top_left.y..=bottom_right.y {
+            let from = source(Point::new(visible.top_left.x, y));
+            let row = &src[from..from + len];
+
+            #[allow(clippy::cast_sign_loss)]
+            let (x, y) = (visible.top_left.x as u32, y as u32);
+            match self.display_rotation {
+                DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
+                    let start = self.pixel_index(x, y);
+                    self.mode.buffer.as_mut()[start..start + len].copy_from_slice(row);
+                }
+                DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
+                    for (x, &value) in (x..).zip(row) {
+                        let idx = self.pixel_index(x, y);
+                        self.mode.buffer.as_mut()[idx] = value;
+                    }
+                }
+            }
+        }
+
+        #[allow(clippy::cast_sign_loss)]
+        self.mode.mark_dirty(
+            (visible.top_left.x as u16, visible.top_left.y as u16),
+            (bottom_right.x as u16, bottom_right.y as u16),
+        );
+    }
+
+    /// Zero the display buffer inside `area` and mark exactly that area dirty, e.g. to
+    /// redraw a band of the screen before [`flush_region`](Self::flush_region).
+    /// NOTE: Must use `flush` to apply changes
+    ///
+    /// `area` is clamped to the display and the clip, an empty result does nothing. Like
+    /// [`set_pixels`](Self::set_pixels), the coordinates are not transformed.
+    #[cfg(feature = "graphics")]
+    pub fn clear_region(&mut self, area: Rectangle) {
+        self.fill_rect(self.visible_rect(&area), 0);
+    }
+
+    /// Draw packed pixel `bytes` into `area` of the framebuffer, row by row.
+    ///
+    /// This takes decoder output as is: Rgb565 in either byte order, or Rgb888 packed to
+    /// Rgb565 on the fly. Like [`set_pixels`](Self::set_pixels), the coordinates are not
+    /// transformed and the pixels outside the clip are skipped.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if `area` does not entirely fit
+    /// on the display or `bytes` is not exactly its pixels in `format`.
+    #[cfg(feature = "graphics")]
+    pub fn draw_bytes(
+        &mut self,
+        area: Rectangle,
+        bytes: &[u8],
+        format: PixelByteFormat,
+    ) -> Result<(), DisplayError> {
+        self.check_bytes_fit(&area, bytes, format)?;
+
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+
+        self.set_pixels_clipped(
+            (area.top_left.x, area.top_left.y),
+            (bottom_right.x, bottom_right.y),
+            format.pixels(bytes),
+        )
+        .map(|_clipped| ())
+    }
+
+    /// Fill `area` of the framebuffer by repeating `pattern`, a tile of `pattern_size`
+    /// pixels in row-major order.
+    ///
+    /// The tiles start at the upper left corner of `area`. Tiles that don't evenly divide
+    /// `area` are cut at its right and bottom edges, and the parts of `area` outside of the
+    /// display or the clip are ignored. Like [`set_pixels`](Self::set_pixels), the
+    /// coordinates are not transformed.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DisplayError::OutOfBoundsError`] if `pattern` is empty or not
+    /// exactly `pattern_size` pixels.
+    #[cfg(feature = "graphics")]
+    pub fn fill_tiled(
+        &mut self,
+        area: Rectangle,
+        pattern: &[u16],
+        pattern_size: Size,
+    ) -> Result<(), DisplayError> {
+        Self::check_pattern(pattern, pattern_size)?;
+
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+
+        self.set_pixels_clipped(
+            (area.top_left.x, area.top_left.y),
+            (bottom_right.x, bottom_right.y),
+            Self::tile_colors(area.top_left, area, pattern, pattern_size),
+        )
+        .map(|_clipped| ())
+    }
+
+    /// Set the transform applied to the coordinates given to [`set_pixel`](Self::set_pixel),
+    /// [`blend_pixel`](Self::blend_pixel) and the embedded-graphics `draw_iter`.
+    ///
+    /// Transformed points falling outside the display are discarded. Bulk writes such as
+    /// [`set_pixels`](Self::set_pixels) are not transformed. `None` removes the transform.
+    pub const fn set_transform(&mut self, transform: Option<Affine2>) {
+        self.mode.transform = transform;
+    }
+
+    /// Apply the current transform to the `x` and `y` coordinates, `None` if they land off
+    /// screen.
+    ///
+    /// Without transform the coordinates are only checked against the display.
+    fn transform_pixel(&self, x: u32, y: u32) -> Option<(u32, u32)> {
+        if self.mode.transform.is_none() {
+            let (width, height) = self.dimensions();
+            return (x < width.into() && y < height.into()).then_some((x, y));
+        }
+
+        self.transform_point(i32::try_from(x).ok()?, i32::try_from(y).ok()?)
+    }
+
+    /// Apply the current transform to a signed point, `None` if it lands off screen
+    fn transform_point(&self, x: i32, y: i32) -> Option<(u32, u32)> {
+        let (x, y) = self
+            .mode
+            .transform
+            .map_or((x, y), |transform| transform.apply(x, y));
+        let (x, y) = (u32::try_from(x).ok()?, u32::try_from(y).ok()?);
+        let (width, height) = self.dimensions();
+
+        (x < width.into() && y < height.into()).then_some((x, y))
+    }
+
+    /// Write a pixel in the framebuffer and grow the dirty box, without any transform
+    fn set_pixel_raw(&mut self, x: u32, y: u32, value: u16) {
+        if let Some((start, end)) = self.mode.clip {
+            let inside = |v: u32, from: u16, to: u16| v >= from.into() && v < to.into();
+            if !inside(x, start.0, end.0) || !inside(y, start.1, end.1) {
+                return;
+            }
+        }
+
+        let idx = self.pixel_index(x, y);
+
+        if let Some(color) = self.mode.buffer.as_mut().get_mut(idx) {
+            *color = value;
+
+            // the box can't grow any further
+            if self.mode.all_dirty {
+                return;
+            }
+
+            self.mode.min_x = self.mode.min_x.min(x as u16);
+            self.mode.max_x = self.mode.max_x.max(x as u16);
+            self.mode.min_y = self.mode.min_y.min(y as u16);
+            self.mode.max_y = self.mode.max_y.max(y as u16);
+        }
+    }
+
+    /// Framebuffer index of the `x` and `y` coordinates for the current rotation
+    const fn pixel_index(&self, x: u32, y: u32) -> usize {
+        match self.display_rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
+                ((y as usize) * D::WIDTH as usize) + (x as usize)
+            }
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
+                ((x as usize) * D::WIDTH as usize) + (y as usize)
+            }
+        }
+    }
+}
+
+/// Interpolate between `from` and `to` (both raw Rgb565) at `step` of `span`, channel by
+/// channel
+#[cfg(feature = "graphics")]
+const fn lerp_rgb565(from: u16, to: u16, step: u32, span: u32) -> u16 {
+    const fn mix(from: u16, to: u16, step: u32, span: u32) -> u16 {
+        ((from as u32 * (span - step) + to as u32 * step + span / 2) / span) as u16
+    }
+
+    if span == 0 {
+        return from;
+    }
+
+    let r = mix(from >> 11, to >> 11, step, span);
+    let g = mix((from >> 5) & 0x3F, (to >> 5) & 0x3F, step, span);
+    let b = mix(from & 0x1F, to & 0x1F, step, span);
+
+    (r << 11) | (g << 5) | b
+}
+
+/// Blend `src` over `dst` (both raw Rgb565) with `alpha` coverage, channel by channel
+#[cfg(feature = "graphics")]
+const fn blend_rgb565(dst: u16, src: u16, alpha: u8) -> u16 {
+    const fn mix(dst: u16, src: u16, alpha: u16) -> u16 {
+        (src * alpha + dst * (255 - alpha) + 127) / 255
+    }
+
+    let alpha = alpha as u16;
+    let r = mix(dst >> 11, src >> 11, alpha);
+    let g = mix((dst >> 5) & 0x3F, (src >> 5) & 0x3F, alpha);
+    let b = mix(dst & 0x1F, src & 0x1F, alpha);
+
+    (r << 11) | (g << 5) | b
+}
+
+#[cfg(feature = "graphics")]
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::Size,
+    geometry::{Dimensions, OriginDimensions, Point},
+    pixelcolor::raw::RawU16,
+    pixelcolor::Rgb565,
+    prelude::RawData,
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+#[cfg(feature = "graphics")]
+use crate::pixel::PixelByteFormat;
+
+#[cfg(feature = "image")]
+use embedded_graphics::{
+    draw_target::DrawTargetExt,
+    image::{ImageDrawable, ImageRaw},
+    pixelcolor::raw::ByteOrder,
+};
+
+#[cfg(feature = "graphics")]
+impl<I, D> OriginDimensions for Gc9a01<I, D, BufferedGraphics<D>>
+where
+    I: WriteOnlyDataCommand,
+    D: DisplayDefinition,
+{
+    fn size(&self) -> Size {
+        let (w, h) = self.dimensions();
+        Size::new(w.into(), h.into())
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl<I, D> DrawTarget for Gc9a01<I, D, BufferedGraphics<D>>
+where
+    I: WriteOnlyDataCommand,
+    D: DisplayDefinition,
+{
+    // TODO: figure out a way to handle all case
+    type Color = Rgb565;
+    type Error = DisplayError;
+
+    fn draw_iter<O>(&mut self, pixels: O) -> Result<(), Self::Error>
+    where
+        O: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bb = self.bounding_box();
+
+        if self.mode.transform.is_some() {
+            pixels.into_iter().for_each(|Pixel(pos, color)| {
+                if let Some((x, y)) = self.transform_point(pos.x, pos.y) {
+                    self.set_pixel_raw(x, y, RawU16::from(color).into_inner());
+                }
+            });
+            return Ok(());
+        }
+
+        pixels
+            .into_iter()
+            .filter(|&Pixel(pos, _color)| bb.contains(pos))
+            .for_each(|Pixel(pos, color)| {
+                let color: RawU16 = color.into();
+                let color: u16 = color.into_inner();
+                #[allow(clippy::cast_sign_loss)]
+                self.set_pixel_raw(pos.x as u32, pos.y as u32, color);
+            });
+        Ok(())
+    }
+
+    fn fill_contiguous<C>(&mut self, area: &Rectangle, colors: C) -> Result<(), Self::Error>
+    where
+        C: IntoIterator<Item = Self::Color>,
+    {
+        if self.mode.transform.is_some() {
+            return self.draw_iter(
+                area.points()
+                    .zip(colors)
+                    .map(|(pos, color)| Pixel(pos, color)),
+            );
+        }
+
+        let visible = self.visible_rect(area);
+        let Some(bottom_right) = visible.bottom_right() else {
+            return Ok(());
+        };
+
+        for (pos, color) in area.points().zip(colors) {
+            if visible.contains(pos) {
+                #[allow(clippy::cast_sign_loss)]
+                let idx = self.pixel_index(pos.x as u32, pos.y as u32);
+                self.mode.buffer.as_mut()[idx] = RawU16::from(color).into_inner();
+            }
+        }
+
+        #[allow(clippy::cast_sign_loss)]
+        self.mode.mark_dirty(
+            (visible.top_left.x as u16, visible.top_left.y as u16),
+            (bottom_right.x as u16, bottom_right.y as u16),
+        );
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        // transformed rectangles are not rectangles anymore
+        if self.mode.transform.is_some() {
+            return self.draw_iter(area.points().map(|pos| Pixel(pos, color)));
+        }
+
+        self.fill_rect(self.visible_rect(area), RawU16::from(color).into_inner());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        capture::{params, CaptureInterface},
+        display::DisplayResolution240x240,
+    };
+
+    type Display<const N: usize> = Gc9a01<
+        CaptureInterface<N>,
+        DisplayResolution240x240,
+        BufferedGraphics<DisplayResolution240x240>,
+    >;
+
+    fn display<const N: usize>() -> Display<N> {
+        Gc9a01::new(
+            CaptureInterface::new(),
+            DisplayResolution240x240,
+            DisplayRotation::Rotate0,
+        )
+        .into_buffered_graphics()
+    }
+
+    /// Pixels of the 5 x 4 area at the origin, row by row
+    fn corner<const N: usize>(display: &Display<N>) -> [[u16; 5]; 4] {
+        core::array::from_fn(|y| {
+            core::array::from_fn(|x| display.mode.buffer[display.pixel_index(x as u32, y as u32)])
+        })
+    }
+
+    #[test]
+    fn set_pixels_fills_a_rectangle() -> Result<(), DisplayError> {
+        let mut display = display::<0>();
+        display.set_pixels((1, 1), (3, 2), 1..=6)?;
+
+        assert_eq!(
+            corner(&display),
+            [
+                [0, 0, 0, 0, 0],
+                [0, 1, 2, 3, 0],
+                [0, 4, 5, 6, 0],
+                [0, 0, 0, 0, 0]
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_pixels_columns_fills_a_rectangle() -> Result<(), DisplayError> {
+        let mut display = display::<0>();
+        display.set_pixels_columns((1, 1), (3, 2), 1..=6)?;
+
+        assert_eq!(
+            corner(&display),
+            [
+                [0, 0, 0, 0, 0],
+                [0, 1, 3, 5, 0],
+                [0, 2, 4, 6, 0],
+                [0, 0, 0, 0, 0]
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn clear_sends_only_the_drawn_rows() -> Result<(), DisplayError> {
+        let mut display = display::<1024>();
+        display.set_pixels_columns((100, 50), (109, 53), core::iter::repeat(0xFFFF))?;
+        display.flush()?;
+        let drawn = display.interface().captured().len();
+
+        display.clear();
+        display.flush()?;
+
+        let captured = &display.interface().captured()[drawn..];
+        let rows: Vec<_> = (50..=53).map(|row| [0, row, 0, row]).collect();
+        assert_eq!(params(captured, 0x2B), rows);
+        assert_eq!(params(captured, 0x2A), [[0, 100, 0, 109]; 4]);
+        assert!(params(captured, 0x2C)
+            .iter()
+            .all(|pixels| pixels.iter().all(|&byte| byte == 0)));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn red_is_sent_big_endian() -> Result<(), DisplayError> {
+        use embedded_graphics_core::{
+            pixelcolor::{Rgb565, RgbColor},
+            Drawable, Pixel,
+        };
+
+        let mut display = display::<64>();
+        Pixel(Point::new(120, 120), Rgb565::RED).draw(&mut display)?;
+        display.flush()?;
+
+        assert_eq!(params(display.interface().captured(), 0x2C), [[0xF8, 0x00]]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn flush_with_leaves_the_buffer_untouched() -> Result<(), DisplayError> {
+        let mut display = display::<64>();
+        display.set_pixel(1, 1, 0x1234);
+
+        let mut wire = Vec::new();
+        display.flush_with(|bytes| {
+            wire.extend_from_slice(bytes);
+            Ok(())
+        })?;
+        assert_eq!(wire.len(), 240 * 2);
+        assert_eq!(wire[2..4], [0x12, 0x34]);
+
+        display.set_pixel(1, 1, 0x5678);
+        let failed = display.flush_with(|_bytes| Err(DisplayError::BusWriteError));
+        assert!(matches!(failed, Err(DisplayError::BusWriteError)));
+        assert_eq!(display.get_pixel(1, 1), Some(0x5678));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn set_clip_accepts_huge_rectangles() {
+        let mut display = display::<0>();
+        display.set_clip(Some(Rectangle::new(
+            Point::new(10, 100),
+            Size::new(u32::MAX, 20),
+        )));
+
+        display.set_pixel(239, 119, 1);
+        display.set_pixel(9, 110, 1);
+        display.set_pixel(20, 120, 1);
+
+        assert_eq!(display.get_pixel(239, 119), Some(1));
+        assert_eq!(display.get_pixel(9, 110), Some(0));
+        assert_eq!(display.get_pixel(20, 120), Some(0));
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn bulk_writes_stay_in_the_clip() -> Result<(), DisplayError> {
+        let mut display = display::<0>();
+        display.set_clip(Some(Rectangle::new(Point::new(1, 1), Size::new(2, 2))));
+
+        display.set_pixels((0, 0), (4, 3), core::iter::repeat(1))?;
+        display.set_pixels_columns((0, 0), (4, 3), core::iter::repeat(2))?;
+        let clipped = display.set_pixels_clipped((-1, 0), (4, 3), core::iter::repeat(3))?;
+        display.fill_tiled(
+            Rectangle::new(Point::zero(), Size::new(5, 4)),
+            &[4],
+            Size::new(1, 1),
+        )?;
+        display.clear_region(Rectangle::new(Point::new(2, 0), Size::new(3, 4)));
+
+        assert_eq!(clipped, 6 * 4 - 4);
+        assert_eq!(
+            corner(&display),
+            [
+                [0, 0, 0, 0, 0],
+                [0, 4, 0, 0, 0],
+                [0, 4, 0, 0, 0],
+                [0, 0, 0, 0, 0]
+            ]
+        );
+        assert_eq!(
+            display.dirty_bounds(),
+            Some(Rectangle::new(Point::new(1, 1), Size::new(2, 2)))
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn rings_far_away_draw_nothing() {
+        use embedded_graphics_core::pixelcolor::{Rgb565, RgbColor};
+
+        let mut display = display::<0>();
+        for center in [
+            Point::new(i32::MAX / 2, i32::MAX / 2),
+            Point::new(i32::MAX, i32::MIN),
+            Point::new(i32::MIN, 120),
+        ] {
+            display.fill_ring(center, 10, u16::MAX, Rgb565::WHITE);
+            display.fill_arc(center, 10, u16::MAX, i32::MAX - 10, i32::MAX, Rgb565::WHITE);
+        }
+
+        assert_eq!(display.dirty_bounds(), None);
+    }
+
+    #[test]
+    fn mark_all_dirty_follows_the_rotation() -> Result<(), DisplayError> {
+        use crate::display::DisplayResolution240x280;
+
+        let mut display = Gc9a01::new(
+            CaptureInterface::<256>::new(),
+            DisplayResolution240x280,
+            DisplayRotation::Rotate0,
+        )
+        .into_buffered_graphics();
+        display.mark_all_dirty();
+        display.set_display_rotation(DisplayRotation::Rotate90)?;
+        display.set_pixel(270, 10, 0x1234);
+
+        let mut wire = Vec::new();
+        display.flush_with(|bytes| {
+            wire.extend_from_slice(bytes);
+            Ok(())
+        })?;
+
+        assert_eq!(wire.len(), 280 * 240 * 2);
+        // the frame goes out in panel order, where rotated x is the row
+        assert_eq!(wire[(270 * 240 + 10) * 2..][..2], [0x12, 0x34]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn flush_synced_gives_up_without_te() {
+        struct NoDelay;
+
+        impl DelayNs for NoDelay {
+            fn delay_ns(&mut self, _ns: u32) {}
+        }
+
+        struct Unwired;
+
+        impl embedded_hal::digital::ErrorType for Unwired {
+            type Error = core::convert::Infallible;
+        }
+
+        impl InputPin for Unwired {
+            fn is_high(&mut self) -> Result<bool, Self::Error> {
+                Ok(false)
+            }
+
+            fn is_low(&mut self) -> Result<bool, Self::Error> {
+                Ok(true)
+            }
+        }
+
+        let mut display = display::<64>();
+        display.set_pixel(1, 1, 0xFFFF);
+
+        let flushed = display.flush_synced(&mut Unwired, &mut NoDelay);
+
+        assert!(matches!(flushed, Err(DisplayError::BusWriteError)));
+        assert!(display.interface().captured().is_empty());
+    }
 }
@@ -31,6 +31,8 @@ where
 
     /// Initializes and configures the display for the given mode.
     ///
+    /// Run it after a hardware reset, or after `soft_reset` when the board has no RST line.
+    ///
     /// # Errors
     ///
     /// This method may return an error if there are communication issues with the display.
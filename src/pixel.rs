@@ -0,0 +1,133 @@
+//! Pixel Byte Formats
+//!
+//! Describe the packed pixel bytes produced by image decoders, and adapt other color types to
+//! the driver draw targets
+
+/// Byte layout of the pixels given to `draw_bytes`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PixelByteFormat {
+    /// Rgb565, two bytes per pixel, least significant byte first
+    Rgb565LE,
+    /// Rgb565, two bytes per pixel, most significant byte first (panel order)
+    Rgb565BE,
+    /// Rgb888, three bytes per pixel in red, green, blue order, packed to Rgb565 on the fly
+    Rgb888,
+}
+
+impl PixelByteFormat {
+    /// Number of bytes of one pixel
+    #[must_use]
+    pub const fn bytes_per_pixel(self) -> usize {
+        match self {
+            Self::Rgb565LE | Self::Rgb565BE => 2,
+            Self::Rgb888 => 3,
+        }
+    }
+
+    /// Decode `bytes` into native Rgb565 values, ignoring a trailing partial pixel
+    #[cfg(feature = "graphics")]
+    pub(crate) fn pixels(self, bytes: &[u8]) -> impl Iterator<Item = u16> + '_ {
+        bytes
+            .chunks_exact(self.bytes_per_pixel())
+            .map(move |pixel| match (self, pixel) {
+                (Self::Rgb565LE, &[lo, hi]) => u16::from_le_bytes([lo, hi]),
+                (Self::Rgb565BE, &[hi, lo]) => u16::from_be_bytes([hi, lo]),
+                (Self::Rgb888, &[r, g, b]) => {
+                    (u16::from(r >> 3) << 11) | (u16::from(g >> 2) << 5) | u16::from(b >> 3)
+                }
+                _ => 0,
+            })
+    }
+}
+
+#[cfg(feature = "graphics")]
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::Dimensions,
+    pixelcolor::{Bgr565, Rgb565},
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// `Bgr565` Draw Target
+///
+/// Every driver mode draws `Rgb565`, which the init sequence maps onto the BGR filter of the
+/// panel through the MADCTL BGR bit. This adapter lets `Bgr565` assets draw on any of them,
+/// swapping the channels on the way while keeping the fast `fill_solid` and
+/// `fill_contiguous` paths of the wrapped target.
+///
+/// ```ignore
+/// let mut bgr = Bgr565Target::new(&mut display);
+/// Rectangle::new(Point::zero(), Size::new(40, 40))
+///     .into_styled(PrimitiveStyle::with_fill(Bgr565::RED))
+///     .draw(&mut bgr)?;
+/// ```
+#[cfg(feature = "graphics")]
+#[derive(Debug)]
+pub struct Bgr565Target<'a, T> {
+    target: &'a mut T,
+}
+
+#[cfg(feature = "graphics")]
+impl<'a, T> Bgr565Target<'a, T>
+where
+    T: DrawTarget<Color = Rgb565>,
+{
+    /// Wrap an `Rgb565` draw target
+    #[must_use]
+    pub const fn new(target: &'a mut T) -> Self {
+        Self { target }
+    }
+
+    /// Release the wrapped draw target
+    #[must_use]
+    pub const fn release(self) -> &'a mut T {
+        self.target
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl<T> Dimensions for Bgr565Target<'_, T>
+where
+    T: DrawTarget<Color = Rgb565>,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl<T> DrawTarget for Bgr565Target<'_, T>
+where
+    T: DrawTarget<Color = Rgb565>,
+{
+    type Color = Bgr565;
+    type Error = T::Error;
+
+    fn draw_iter<P>(&mut self, pixels: P) -> Result<(), Self::Error>
+    where
+        P: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(pos, color)| Pixel(pos, Rgb565::from(color))),
+        )
+    }
+
+    fn fill_contiguous<C>(&mut self, area: &Rectangle, colors: C) -> Result<(), Self::Error>
+    where
+        C: IntoIterator<Item = Self::Color>,
+    {
+        self.target
+            .fill_contiguous(area, colors.into_iter().map(Rgb565::from))
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.target.fill_solid(area, color.into())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.target.clear(color.into())
+    }
+}
@@ -4,8 +4,18 @@ pub use display_interface::WriteOnlyDataCommand;
 pub use display_interface_spi::SPIInterface;
 
 pub use super::{
-    brightness::Brightness,
-    display::{DisplayDefinition, DisplayResolution240x240},
+    brightness::{Brightness, ZeroBrightnessPolicy},
+    command::MadctlBuilder,
+    display::{
+        DisplayDefinition, DisplayResolution128x128, DisplayResolution240x240,
+        DisplayResolution240x280, DisplayResolution240x320, DisplayResolutionGeneric, InitStep,
+    },
+    gamma::GammaBuilder,
     mode::DisplayConfiguration,
+    pixel::PixelByteFormat,
     rotation::DisplayRotation,
+    transform::Affine2,
 };
+
+#[cfg(feature = "graphics")]
+pub use super::pixel::Bgr565Target;
@@ -1,16 +1,11 @@
 //! Display Rotation
 
 /// Screen Rotation
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum DisplayRotation {
+    #[default]
     Rotate0,
     Rotate90,
     Rotate180,
     Rotate270,
 }
-
-impl Default for DisplayRotation {
-    fn default() -> Self {
-        Self::Rotate0
-    }
-}
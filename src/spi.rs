@@ -1,18 +1,425 @@
 //! SPI Display Interface
 
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
 use display_interface_spi::SPIInterface;
+use embedded_hal::{digital::OutputPin, spi::SpiBus};
+
+/// Size of the stack buffer used to serialize iterators and 16-bit words
+const BUFFER_SIZE: usize = 64;
 
 /// SPI Interfaces for the screen
+///
+/// Pick the constructor by who drives the chip select:
+///
+/// * [`new`](Self::new) takes a [`SpiDevice`](embedded_hal::spi::SpiDevice), which asserts
+///   CS itself around every transfer. HALs usually hand out a bus: wrap it and the CS pin in
+///   an `ExclusiveDevice` (embedded-hal-bus) first, or use `new_bus`.
+/// * `new_async` (`async` feature) is the same over an async
+///   [`SpiDevice`](embedded_hal_async::spi::SpiDevice), for the `_async` flushes.
+/// * [`new_bus`](Self::new_bus) takes a [`SpiBus`] and the CS pin, and the interface drives
+///   CS itself. The bus can't be shared with other devices.
+/// * [`new_no_cs`](Self::new_no_cs) takes a [`SpiBus`] alone, for panels whose CS is tied low
+///   or driven by the SPI peripheral.
+/// * [`new_3wire`](Self::new_3wire) takes a [`SpiBus`] and the CS pin of a panel without DC
+///   line, and drives CS itself.
 #[derive(Debug, Copy, Clone)]
 pub struct SPIDisplayInterface(());
 
 impl SPIDisplayInterface {
+    /// Create an interface over an SPI device, which manages CS
+    ///
+    /// Any [`SpiDevice`](embedded_hal::spi::SpiDevice) is accepted, so the bus can be shared
+    /// with other devices (touch controller, SD card) through the `embedded-hal-bus`
+    /// wrappers: `RefCellDevice` within a single context, `CriticalSectionDevice` when the
+    /// bus is also used from interrupts or other cores.
+    ///
+    /// ```ignore
+    /// let bus = RefCell::new(spi_bus);
+    /// let display_spi = RefCellDevice::new_no_delay(&bus, display_cs)?;
+    /// let touch_spi = RefCellDevice::new_no_delay(&bus, touch_cs)?;
+    ///
+    /// let interface = SPIDisplayInterface::new(display_spi, dc);
+    /// ```
+    ///
+    /// Every command and data write is its own transaction, so the other devices can use the
+    /// bus between any two of them.
     #[allow(clippy::new_ret_no_self)]
     pub fn new<SPI, DC>(spi: SPI, dc: DC) -> SPIInterface<SPI, DC>
     where
         SPI: embedded_hal::spi::SpiDevice,
-        DC: embedded_hal::digital::OutputPin,
+        DC: OutputPin,
+    {
+        SPIInterface::new(spi, dc)
+    }
+
+    /// Create an interface over an async SPI device, which manages CS
+    ///
+    /// The interface implements `AsyncWriteOnlyDataCommand`, as required by the `_async`
+    /// methods of the driver.
+    #[cfg(feature = "async")]
+    pub fn new_async<SPI, DC>(spi: SPI, dc: DC) -> SPIInterface<SPI, DC>
+    where
+        SPI: embedded_hal_async::spi::SpiDevice,
+        DC: OutputPin,
     {
         SPIInterface::new(spi, dc)
     }
+
+    /// Create an interface driving the chip select itself, see [`SPIBusInterface`]
+    pub const fn new_bus<BUS, DC, CS>(bus: BUS, dc: DC, cs: CS) -> SPIBusInterface<BUS, DC, CS>
+    where
+        BUS: SpiBus,
+        DC: OutputPin,
+        CS: OutputPin,
+    {
+        SPIBusInterface::new(bus, dc, cs)
+    }
+
+    /// Create an interface without chip select, for panels whose CS is tied low or driven
+    /// by the SPI peripheral
+    pub const fn new_no_cs<BUS, DC>(bus: BUS, dc: DC) -> SPIBusInterface<BUS, DC, NoCs>
+    where
+        BUS: SpiBus,
+        DC: OutputPin,
+    {
+        SPIBusInterface::new(bus, dc, NoCs)
+    }
+
+    /// Create a 3-wire interface, without data/command pin, see [`SPI3WireInterface`]
+    pub const fn new_3wire<BUS, CS>(bus: BUS, cs: CS) -> SPI3WireInterface<BUS, CS>
+    where
+        BUS: SpiBus,
+        CS: OutputPin,
+    {
+        SPI3WireInterface::new(bus, cs)
+    }
+}
+
+/// Placeholder chip select pin for [`SPIDisplayInterface::new_no_cs`], every write is a noop
+#[derive(Debug, Copy, Clone, Default)]
+pub struct NoCs;
+
+impl embedded_hal::digital::ErrorType for NoCs {
+    type Error = core::convert::Infallible;
+}
+
+impl OutputPin for NoCs {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// SPI interface sending each command and its data in a single chip select transaction
+///
+/// A [`SpiDevice`](embedded_hal::spi::SpiDevice) asserts CS for every call, so a command
+/// followed by its parameters or pixels costs two transactions. This interface owns the
+/// [`SpiBus`] and the CS pin: CS is asserted by a command, held across the DC toggle and
+/// released at the end of the following data, or before the next command. The `MemoryWrite`
+/// (2Ch) of every `flush` and its first pixel transfer therefore share one transaction.
+///
+/// The bus must not be shared, since CS may stay asserted between a command without
+/// parameters and the next call.
+#[derive(Debug)]
+pub struct SPIBusInterface<BUS, DC, CS> {
+    bus: BUS,
+    dc: DC,
+    cs: CS,
+    selected: bool,
+}
+
+impl<BUS, DC, CS> SPIBusInterface<BUS, DC, CS>
+where
+    BUS: SpiBus,
+    DC: OutputPin,
+    CS: OutputPin,
+{
+    /// Create an interface from the SPI bus, the data/command pin and the chip select pin
+    pub const fn new(bus: BUS, dc: DC, cs: CS) -> Self {
+        Self {
+            bus,
+            dc,
+            cs,
+            selected: false,
+        }
+    }
+
+    /// Consume the interface and return the SPI bus, the data/command pin and the chip
+    /// select pin
+    pub fn release(self) -> (BUS, DC, CS) {
+        (self.bus, self.dc, self.cs)
+    }
+
+    /// Assert CS if no transaction is open
+    fn select(&mut self) -> Result<(), DisplayError> {
+        if !self.selected {
+            self.cs.set_low().map_err(|_error| DisplayError::CSError)?;
+            self.selected = true;
+        }
+        Ok(())
+    }
+
+    /// Wait for the bus to be idle and release CS
+    fn deselect(&mut self) -> Result<(), DisplayError> {
+        if self.selected {
+            self.bus
+                .flush()
+                .map_err(|_error| DisplayError::BusWriteError)?;
+            self.cs.set_high().map_err(|_error| DisplayError::CSError)?;
+            self.selected = false;
+        }
+        Ok(())
+    }
+
+    /// Write `words` to the bus, serializing 16-bit words as requested
+    fn write(&mut self, words: DataFormat<'_>) -> Result<(), DisplayError> {
+        match words {
+            DataFormat::U8(slice) => self
+                .bus
+                .write(slice)
+                .map_err(|_error| DisplayError::BusWriteError),
+            DataFormat::U16(slice) => {
+                self.write_bytes(slice.iter().flat_map(|word| word.to_ne_bytes()))
+            }
+            DataFormat::U16BE(slice) => {
+                self.write_bytes(slice.iter().flat_map(|word| word.to_be_bytes()))
+            }
+            DataFormat::U16LE(slice) => {
+                self.write_bytes(slice.iter().flat_map(|word| word.to_le_bytes()))
+            }
+            DataFormat::U8Iter(iter) => self.write_bytes(iter),
+            DataFormat::U16BEIter(iter) => self.write_bytes(iter.flat_map(u16::to_be_bytes)),
+            DataFormat::U16LEIter(iter) => self.write_bytes(iter.flat_map(u16::to_le_bytes)),
+            _ => Err(DisplayError::DataFormatNotImplemented),
+        }
+    }
+
+    /// Write `bytes` to the bus through a stack buffer
+    fn write_bytes<B>(&mut self, bytes: B) -> Result<(), DisplayError>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        let mut buf = [0u8; BUFFER_SIZE];
+        let mut len = 0;
+
+        for byte in bytes {
+            if let Some(slot) = buf.get_mut(len) {
+                *slot = byte;
+                len += 1;
+            }
+
+            if len == BUFFER_SIZE {
+                self.bus
+                    .write(&buf)
+                    .map_err(|_error| DisplayError::BusWriteError)?;
+                len = 0;
+            }
+        }
+
+        match buf.get(..len) {
+            Some(rest) if !rest.is_empty() => self
+                .bus
+                .write(rest)
+                .map_err(|_error| DisplayError::BusWriteError),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<BUS, DC, CS> WriteOnlyDataCommand for SPIBusInterface<BUS, DC, CS>
+where
+    BUS: SpiBus,
+    DC: OutputPin,
+    CS: OutputPin,
+{
+    fn send_commands(&mut self, cmds: DataFormat<'_>) -> Result<(), DisplayError> {
+        // a command starts a new transaction
+        self.deselect()?;
+        self.select()?;
+
+        // 1 = data, 0 = command
+        self.dc.set_low().map_err(|_error| DisplayError::DCError)?;
+        let result = self.write(cmds);
+        if result.is_err() {
+            self.deselect()?;
+        }
+        result
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.select()?;
+
+        // the bus must be idle before DC switches to data
+        self.bus
+            .flush()
+            .map_err(|_error| DisplayError::BusWriteError)?;
+        // 1 = data, 0 = command
+        self.dc.set_high().map_err(|_error| DisplayError::DCError)?;
+
+        let result = self.write(buf);
+        self.deselect()?;
+        result
+    }
+}
+
+/// An interface able to read registers back from the controller
+///
+/// Reading requires the panel SDO line wired to the MCU (MISO), which many modules leave
+/// unconnected. Without it, reads return whatever the floating line gives.
+pub trait ReadableInterface: WriteOnlyDataCommand {
+    /// Send `command` and clock `buf.len()` bytes back, in a single chip select transaction
+    ///
+    /// The bytes are returned as clocked in: any dummy cycle the command has is left in
+    /// place for the caller to strip.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an error if there are communication issues with the display.
+    fn read_data(&mut self, command: u8, buf: &mut [u8]) -> Result<(), DisplayError>;
+}
+
+impl<BUS, DC, CS> ReadableInterface for SPIBusInterface<BUS, DC, CS>
+where
+    BUS: SpiBus,
+    DC: OutputPin,
+    CS: OutputPin,
+{
+    fn read_data(&mut self, command: u8, buf: &mut [u8]) -> Result<(), DisplayError> {
+        self.send_commands(DataFormat::U8(&[command]))?;
+
+        // the bus must be idle before DC switches to data
+        self.bus
+            .flush()
+            .map_err(|_error| DisplayError::BusWriteError)?;
+        // 1 = data, 0 = command
+        self.dc.set_high().map_err(|_error| DisplayError::DCError)?;
+
+        let result = self
+            .bus
+            .read(buf)
+            .map_err(|_error| DisplayError::BusWriteError);
+        self.deselect()?;
+        result
+    }
+}
+
+/// 3-wire SPI interface, for panels wired without DC line (SCL, SDA and CS only)
+///
+/// Every byte goes out as a 9-bit word whose first bit is the data/command flag
+/// (1 = data, 0 = command). Words are packed MSB first into the 8-bit [`SpiBus`] transfers,
+/// and each command or data write is its own CS transaction, so the incomplete word padding
+/// the last byte is dropped by the controller when CS rises. The panel has to be strapped
+/// for the 3-wire interface (IM pins), and the CS pin is mandatory.
+#[derive(Debug)]
+pub struct SPI3WireInterface<BUS, CS> {
+    bus: BUS,
+    cs: CS,
+}
+
+impl<BUS, CS> SPI3WireInterface<BUS, CS>
+where
+    BUS: SpiBus,
+    CS: OutputPin,
+{
+    /// Create an interface from the SPI bus and the chip select pin
+    pub const fn new(bus: BUS, cs: CS) -> Self {
+        Self { bus, cs }
+    }
+
+    /// Consume the interface and return the SPI bus and the chip select pin
+    pub fn release(self) -> (BUS, CS) {
+        (self.bus, self.cs)
+    }
+
+    /// Send `words` in one CS transaction, every byte flagged with `dc`
+    fn transaction(&mut self, dc: bool, words: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.cs.set_low().map_err(|_error| DisplayError::CSError)?;
+
+        let result = match words {
+            DataFormat::U8(slice) => self.write_words(dc, slice.iter().copied()),
+            DataFormat::U16(slice) => {
+                self.write_words(dc, slice.iter().flat_map(|word| word.to_ne_bytes()))
+            }
+            DataFormat::U16BE(slice) => {
+                self.write_words(dc, slice.iter().flat_map(|word| word.to_be_bytes()))
+            }
+            DataFormat::U16LE(slice) => {
+                self.write_words(dc, slice.iter().flat_map(|word| word.to_le_bytes()))
+            }
+            DataFormat::U8Iter(iter) => self.write_words(dc, iter),
+            DataFormat::U16BEIter(iter) => self.write_words(dc, iter.flat_map(u16::to_be_bytes)),
+            DataFormat::U16LEIter(iter) => self.write_words(dc, iter.flat_map(u16::to_le_bytes)),
+            _ => Err(DisplayError::DataFormatNotImplemented),
+        }
+        .and_then(|()| {
+            self.bus
+                .flush()
+                .map_err(|_error| DisplayError::BusWriteError)
+        });
+
+        self.cs.set_high().map_err(|_error| DisplayError::CSError)?;
+        result
+    }
+
+    /// Pack `bytes` into 9-bit words flagged with `dc` and write them through a stack buffer
+    fn write_words<B>(&mut self, dc: bool, bytes: B) -> Result<(), DisplayError>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        let mut buf = [0u8; BUFFER_SIZE];
+        let mut len = 0;
+        // pending bits, right aligned
+        let mut bits = 0u32;
+        let mut bit_count = 0;
+
+        for byte in bytes {
+            bits = (bits << 9) | (u32::from(dc) << 8) | u32::from(byte);
+            bit_count += 9;
+
+            while bit_count >= 8 {
+                bit_count -= 8;
+                buf[len] = (bits >> bit_count) as u8;
+                len += 1;
+
+                if len == BUFFER_SIZE {
+                    self.bus
+                        .write(&buf)
+                        .map_err(|_error| DisplayError::BusWriteError)?;
+                    len = 0;
+                }
+            }
+            bits &= (1 << bit_count) - 1;
+        }
+
+        // pad the last word, dropped when CS rises
+        if bit_count > 0 {
+            buf[len] = (bits << (8 - bit_count)) as u8;
+            len += 1;
+        }
+
+        match buf.get(..len) {
+            Some(rest) if !rest.is_empty() => self
+                .bus
+                .write(rest)
+                .map_err(|_error| DisplayError::BusWriteError),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<BUS, CS> WriteOnlyDataCommand for SPI3WireInterface<BUS, CS>
+where
+    BUS: SpiBus,
+    CS: OutputPin,
+{
+    fn send_commands(&mut self, cmds: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.transaction(false, cmds)
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.transaction(true, buf)
+    }
 }
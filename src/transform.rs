@@ -0,0 +1,133 @@
+//! Coordinate Transform
+//!
+//! Integer affine transform applied to pixel coordinates before they reach the framebuffer.
+
+/// Fixed-point shift, coefficients are Q16.16
+const SHIFT: u32 = 16;
+
+/// Fixed-point one
+const ONE: i32 = 1 << SHIFT;
+
+/// `sin(0..=90°)` in Q16.16
+const SIN_TABLE: [i32; 91] = [
+    0, 1144, 2287, 3430, 4572, 5712, 6850, 7987, 9121, 10252, 11380, 12505, 13626, 14742, 15855,
+    16962, 18064, 19161, 20252, 21336, 22415, 23486, 24550, 25607, 26656, 27697, 28729, 29753,
+    30767, 31772, 32768, 33754, 34729, 35693, 36647, 37590, 38521, 39441, 40348, 41243, 42126,
+    42995, 43852, 44695, 45525, 46341, 47143, 47930, 48703, 49461, 50203, 50931, 51643, 52339,
+    53020, 53684, 54332, 54963, 55578, 56175, 56756, 57319, 57865, 58393, 58903, 59396, 59870,
+    60326, 60764, 61183, 61584, 61966, 62328, 62672, 62997, 63303, 63589, 63856, 64104, 64332,
+    64540, 64729, 64898, 65048, 65177, 65287, 65376, 65446, 65496, 65526, 65536,
+];
+
+/// 2D affine transform
+///
+/// Maps (`x`, `y`) to (`a * x + b * y + tx`, `c * x + d * y + ty`) using Q16.16 fixed-point
+/// coefficients, so it is usable on cores without an FPU. The results are rounded to the
+/// nearest pixel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Affine2 {
+    a: i32,
+    b: i32,
+    c: i32,
+    d: i32,
+    tx: i32,
+    ty: i32,
+}
+
+impl Default for Affine2 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Affine2 {
+    /// Transform leaving every point in place
+    pub const IDENTITY: Self = Self {
+        a: ONE,
+        b: 0,
+        c: 0,
+        d: ONE,
+        tx: 0,
+        ty: 0,
+    };
+
+    /// Translation by (`dx`, `dy`) pixels
+    #[must_use]
+    pub const fn translate(dx: i32, dy: i32) -> Self {
+        Self {
+            tx: dx << SHIFT,
+            ty: dy << SHIFT,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Rotation by `degrees` around the origin
+    ///
+    /// The screen Y axis points down, so positive angles rotate clockwise on the display.
+    #[must_use]
+    pub const fn rotate_degrees(degrees: i32) -> Self {
+        let sin = sin_degrees(degrees);
+        let cos = sin_degrees(degrees + 90);
+
+        Self {
+            a: cos,
+            b: -sin,
+            c: sin,
+            d: cos,
+            tx: 0,
+            ty: 0,
+        }
+    }
+
+    /// Rotation by `degrees` around the point (`cx`, `cy`)
+    ///
+    /// This is the natural transform for the hands of a round clock face.
+    #[must_use]
+    pub const fn rotate_degrees_around(degrees: i32, cx: i32, cy: i32) -> Self {
+        Self::translate(-cx, -cy)
+            .then(Self::rotate_degrees(degrees))
+            .then(Self::translate(cx, cy))
+    }
+
+    /// Compose two transforms: `self` is applied first, then `next`
+    #[must_use]
+    pub const fn then(self, next: Self) -> Self {
+        Self {
+            a: mul(next.a, self.a) + mul(next.b, self.c),
+            b: mul(next.a, self.b) + mul(next.b, self.d),
+            c: mul(next.c, self.a) + mul(next.d, self.c),
+            d: mul(next.c, self.b) + mul(next.d, self.d),
+            tx: mul(next.a, self.tx) + mul(next.b, self.ty) + next.tx,
+            ty: mul(next.c, self.tx) + mul(next.d, self.ty) + next.ty,
+        }
+    }
+
+    /// Transform the point (`x`, `y`)
+    #[must_use]
+    pub const fn apply(&self, x: i32, y: i32) -> (i32, i32) {
+        let (x, y) = (x as i64, y as i64);
+        let half = 1i64 << (SHIFT - 1);
+
+        let tx = self.a as i64 * x + self.b as i64 * y + self.tx as i64;
+        let ty = self.c as i64 * x + self.d as i64 * y + self.ty as i64;
+
+        (((tx + half) >> SHIFT) as i32, ((ty + half) >> SHIFT) as i32)
+    }
+}
+
+/// Multiply two Q16.16 values
+const fn mul(lhs: i32, rhs: i32) -> i32 {
+    ((lhs as i64 * rhs as i64) >> SHIFT) as i32
+}
+
+/// `sin(degrees)` in Q16.16
+pub(crate) const fn sin_degrees(degrees: i32) -> i32 {
+    let degrees = degrees.rem_euclid(360) as usize;
+
+    match degrees {
+        0..=90 => SIN_TABLE[degrees],
+        91..=180 => SIN_TABLE[180 - degrees],
+        181..=270 => -SIN_TABLE[degrees - 180],
+        _ => -SIN_TABLE[360 - degrees],
+    }
+}